@@ -25,8 +25,12 @@ use dlc_manager::channel::SettledClosingChannel;
 use dlc_manager::contract::ClosedContract;
 use dlc_manager::contract::Contract;
 use dlc_manager::contract::PreClosedContract;
+use dlc_manager::ContractId;
 use dlc_manager::DlcChannelId;
+use dlc_messages::oracle_msgs::OracleAttestation;
 use rust_decimal::Decimal;
+use std::collections::HashSet;
+use std::time::Duration;
 use time::OffsetDateTime;
 use tokio::sync::broadcast::error::RecvError;
 use xxi_node::bitcoin_conversion::to_secp_pk_30;
@@ -34,6 +38,27 @@ use xxi_node::bitcoin_conversion::to_txid_30;
 use xxi_node::node::event::NodeEvent;
 use xxi_node::storage::DlcChannelEvent;
 
+/// How often we run [`Node::reconcile_dlc_channels`] to catch up on any [`DlcChannelEvent`]s
+/// dropped by a lagged subscriber.
+const DLC_CHANNEL_RECONCILIATION_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// How many confirmations a closing transaction (CET, settle/claim, or punish) needs before we
+/// treat the position it closes as irreversibly finalized.
+///
+/// Below this depth a reorg could still evict the transaction, which would leave the DB
+/// reporting a realized PnL that never actually happened.
+///
+/// This does *not* cover a collaborative channel close: that path is finalized through
+/// [`dlc_protocol::DlcProtocolExecutor::finish_dlc_protocol`] directly, whose own position
+/// bookkeeping lives outside this module, so it isn't wired through
+/// [`Node::defer_position_close_until_confirmed`] here.
+const CLOSING_TX_CONFIRMATION_THRESHOLD: u32 = 6;
+
+/// How often we run [`Node::reconcile_closing_tx_confirmations`] to check whether any pending
+/// closing transaction has either reached [`CLOSING_TX_CONFIRMATION_THRESHOLD`] or disappeared
+/// due to a reorg.
+const CLOSING_TX_CONFIRMATION_INTERVAL: Duration = Duration::from_secs(60);
+
 pub enum DlcChannelState {
     Pending,
     Open,
@@ -61,13 +86,112 @@ pub struct DlcChannel {
     pub updated_at: OffsetDateTime,
 }
 
+/// The outcome of [`Node::close_dlc_channel`], so that an operator recovering a stuck channel can
+/// tell whether it was simply abandoned locally, actually force-closed on-chain, or already in a
+/// state where there was nothing left to do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CloseDlcChannelOutcome {
+    /// The channel had not yet broadcast a funding or buffer transaction, so there was nothing to
+    /// close on-chain; it was cancelled locally instead.
+    Cancelled,
+    /// The channel had already broadcast a funding transaction, so it was (force-)closed
+    /// on-chain.
+    Closed,
+    /// The channel was already closing or had already reached a terminal state; there was
+    /// nothing left for this call to do.
+    AlreadyClosed,
+}
+
+/// A position whose closing transaction has been observed but hasn't yet reached
+/// [`CLOSING_TX_CONFIRMATION_THRESHOLD`] confirmations, so its finalization is still deferred.
+struct PendingClosingTx {
+    position_id: i32,
+    closing_txid: Txid,
+    trader_realized_pnl_sat: i64,
+    closing_price: Decimal,
+    required_confirmations: u32,
+}
+
 impl Node {
+    /// Closes a dlc channel in any state, so that a channel stuck in an intermediate state (e.g.
+    /// `Offered` or `Accepted`) can always be recovered by an operator, not just a fully
+    /// established `Signed` one. Each originating state maps to the outcome that's actually
+    /// possible for it: an already-closing or already-terminal channel has nothing left to close.
     pub async fn close_dlc_channel(
         &self,
         channel_id: DlcChannelId,
         is_force_close: bool,
-    ) -> Result<()> {
+    ) -> Result<CloseDlcChannelOutcome> {
         let channel = self.inner.get_dlc_channel_by_id(&channel_id)?;
+
+        match &channel {
+            // No funding transaction has been broadcast for these states yet, so there is nothing
+            // on-chain to force close; abandon the channel locally instead.
+            Channel::Offered(_) | Channel::Accepted(_) => {
+                self.cancel_pending_dlc_channel(&channel)?;
+                Ok(CloseDlcChannelOutcome::Cancelled)
+            }
+            // Fully established: this is the regular (force-)close path, going through
+            // `DlcProtocolType::Close`.
+            Channel::Signed(_) => {
+                self.force_close_established_dlc_channel(&channel, channel_id, is_force_close)
+                    .await?;
+                Ok(CloseDlcChannelOutcome::Closed)
+            }
+            // Already unilaterally closing on-chain (buffer or settle transaction already
+            // broadcast); there is nothing left for this call to trigger.
+            Channel::Closing(_) | Channel::SettledClosing(_) => {
+                tracing::info!(
+                    ?channel_id,
+                    "Dlc channel is already closing on-chain; nothing to do"
+                );
+                Ok(CloseDlcChannelOutcome::AlreadyClosed)
+            }
+            // Already in a terminal state: closing again would either be a no-op or attempt to
+            // force-close a channel rust-dlc no longer tracks.
+            Channel::Closed(_)
+            | Channel::CounterClosed(_)
+            | Channel::CollaborativelyClosed(_)
+            | Channel::ClosedPunished(_)
+            | Channel::FailedAccept(_)
+            | Channel::FailedSign(_) => {
+                tracing::info!(?channel_id, "Dlc channel is already closed; nothing to do");
+                Ok(CloseDlcChannelOutcome::AlreadyClosed)
+            }
+        }
+    }
+
+    /// Abandons a dlc channel that has not yet broadcast a funding or buffer transaction,
+    /// rejecting it in rust-dlc and marking the shadow row `Cancelled`. The dlc protocol that
+    /// created the channel is not updated here.
+    fn cancel_pending_dlc_channel(&self, channel: &Channel) -> Result<()> {
+        let mut conn = self.pool.get()?;
+
+        let reference_id = channel
+            .get_reference_id()
+            .context("dlc channel is missing a reference id")?;
+        let protocol_id = ProtocolId::try_from(reference_id)?;
+
+        self.inner.reject_dlc_channel(&channel.get_id())?;
+
+        db::dlc_channels::set_channel_cancelled(&mut conn, &protocol_id)?;
+
+        tracing::info!(
+            channel_id = ?channel.get_id(),
+            %protocol_id,
+            "Cancelled dlc channel that had not broadcast a funding transaction yet"
+        );
+
+        Ok(())
+    }
+
+    /// (Force-)closes a dlc channel that has already broadcast a funding transaction.
+    async fn force_close_established_dlc_channel(
+        &self,
+        channel: &Channel,
+        channel_id: DlcChannelId,
+        is_force_close: bool,
+    ) -> Result<()> {
         let previous_id = channel.get_reference_id();
         let previous_id = match previous_id {
             Some(previous_id) => Some(ProtocolId::try_from(previous_id)?),
@@ -103,6 +227,18 @@ impl Node {
                 loop {
                     match receiver.recv().await {
                         Ok(NodeEvent::DlcChannelEvent { dlc_channel_event }) => {
+                            // Has to run before `shadow_dlc_channel` below: it compares the
+                            // freshly broadcast buffer/settle transaction against the
+                            // *previously* shadowed txid for this channel.
+                            if let Err(e) =
+                                node.check_for_revoked_channel_broadcast(dlc_channel_event)
+                            {
+                                tracing::error!(
+                                    ?dlc_channel_event,
+                                    "Failed to check for revoked dlc channel broadcast. Error: {e:#}"
+                                );
+                            }
+
                             if let Err(e) = node.shadow_dlc_channel(dlc_channel_event) {
                                 tracing::error!(
                                     ?dlc_channel_event,
@@ -125,6 +261,16 @@ impl Node {
                         | Ok(NodeEvent::SendLastDlcMessage { .. }) => {} // ignored
                         Err(RecvError::Lagged(skipped)) => {
                             tracing::warn!("Skipped {skipped} messages");
+
+                            // A lagged subscriber means we silently dropped `skipped` dlc channel
+                            // events; reconcile immediately instead of waiting for the next
+                            // periodic pass, so the shadow tables don't drift for longer than
+                            // necessary.
+                            if let Err(e) = node.reconcile_dlc_channels() {
+                                tracing::error!(
+                                    "Failed to reconcile dlc channels after lagging. Error: {e:#}"
+                                );
+                            }
                         }
                         Err(RecvError::Closed) => {
                             tracing::error!("Lost connection to sender!");
@@ -136,6 +282,333 @@ impl Node {
         });
     }
 
+    /// Spawns the periodic task that keeps `db::dlc_channels` in sync with rust-dlc's own
+    /// channel store, and runs one pass immediately so we are caught up before serving requests.
+    pub fn spawn_dlc_channel_reconciliation_task(&self) {
+        if let Err(e) = self.reconcile_dlc_channels() {
+            tracing::error!("Failed initial dlc channel reconciliation. Error: {e:#}");
+        }
+
+        tokio::spawn({
+            let node = self.clone();
+            async move {
+                loop {
+                    tokio::time::sleep(DLC_CHANNEL_RECONCILIATION_INTERVAL).await;
+
+                    if let Err(e) = node.reconcile_dlc_channels() {
+                        tracing::error!("Failed to reconcile dlc channels. Error: {e:#}");
+                    }
+                }
+            }
+        });
+    }
+
+    /// Spawns the periodic task that finalizes or reverts positions whose closing transaction is
+    /// pending confirmation.
+    pub fn spawn_closing_tx_confirmation_task(&self) {
+        tokio::spawn({
+            let node = self.clone();
+            async move {
+                loop {
+                    tokio::time::sleep(CLOSING_TX_CONFIRMATION_INTERVAL).await;
+
+                    if let Err(e) = node.reconcile_closing_tx_confirmations().await {
+                        tracing::error!(
+                            "Failed to reconcile closing tx confirmations. Error: {e:#}"
+                        );
+                    }
+                }
+            }
+        });
+    }
+
+    /// Finalizes or reverts every position whose closing transaction is pending confirmation.
+    ///
+    /// A position is finalized (`Closed`, with its recorded PnL applied) once its closing
+    /// transaction reaches [`PendingClosingTx::required_confirmations`]. If the transaction has
+    /// instead disappeared from the chain — evicted by a reorg — the position is reverted back
+    /// to `Closing` and the recorded PnL is discarded, so we never report a settlement that
+    /// didn't actually happen.
+    async fn reconcile_closing_tx_confirmations(&self) -> Result<()> {
+        let mut conn = self.pool.get()?;
+
+        for pending in db::positions::Position::get_positions_pending_closing_tx(&mut conn)? {
+            let confirmations = self
+                .inner
+                .get_transaction_confirmations(&pending.closing_txid)?;
+
+            match pending_closing_tx_outcome(confirmations, pending.required_confirmations) {
+                PendingClosingTxOutcome::Finalize => {
+                    if db::positions::Position::set_position_to_closed_with_pnl(
+                        &mut conn,
+                        pending.position_id,
+                        pending.trader_realized_pnl_sat,
+                        pending.closing_price,
+                    )? > 0
+                    {
+                        tracing::info!(
+                            position_id = pending.position_id,
+                            closing_txid = %pending.closing_txid,
+                            ?confirmations,
+                            "Finalized position now that its closing transaction is reorg-safe"
+                        );
+                    }
+
+                    db::positions::Position::clear_pending_closing_tx(
+                        &mut conn,
+                        pending.position_id,
+                    )?;
+                }
+                PendingClosingTxOutcome::StillPending => {
+                    // Not buried deep enough yet; we'll check again next time around.
+                }
+                PendingClosingTxOutcome::Revert => {
+                    tracing::warn!(
+                        position_id = pending.position_id,
+                        closing_txid = %pending.closing_txid,
+                        "Closing transaction disappeared, likely due to a reorg; reverting \
+                         position to closing"
+                    );
+
+                    db::positions::Position::revert_pending_closing_tx(
+                        &mut conn,
+                        pending.position_id,
+                    )?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reconciles the shadow `db::dlc_channels` table against rust-dlc's own channel store.
+    ///
+    /// The event stream that drives [`Self::shadow_dlc_channel`] is best-effort: a lagged
+    /// subscriber silently drops events, so the shadow tables can permanently drift from
+    /// rust-dlc's view. This walks every channel rust-dlc currently knows about, recomputes the
+    /// expected shadow state exactly as the event handlers would, and patches any discrepancy. It
+    /// also backfills channels missing from the DB entirely (the event that should have inserted
+    /// them was dropped) and marks DB rows `Failed` once the underlying channel has been deleted.
+    /// This turns the event stream into an optimization rather than the sole source of truth.
+    pub fn reconcile_dlc_channels(&self) -> Result<()> {
+        let mut conn = self.pool.get()?;
+
+        let channels = self.inner.list_dlc_channels()?;
+
+        let mut seen_channel_ids = HashSet::with_capacity(channels.len());
+
+        for channel in &channels {
+            let channel_id = channel.get_id();
+            seen_channel_ids.insert(channel_id);
+
+            if let Err(e) = self.reconcile_dlc_channel(&mut conn, channel) {
+                tracing::error!(
+                    ?channel_id,
+                    "Failed to reconcile dlc channel. Error: {e:#}"
+                );
+            }
+        }
+
+        for channel_id in orphaned_channel_ids(
+            db::dlc_channels::get_all_channel_ids(&mut conn)?,
+            &seen_channel_ids,
+        ) {
+            tracing::warn!(
+                ?channel_id,
+                "Dlc channel no longer exists in rust-dlc's store; marking shadow row failed"
+            );
+            db::dlc_channels::set_channel_failed_by_channel_id(&mut conn, &channel_id)?;
+        }
+
+        Ok(())
+    }
+
+    /// Recomputes and, if necessary, patches the shadow row for a single rust-dlc `channel`.
+    fn reconcile_dlc_channel(
+        &self,
+        conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+        channel: &Channel,
+    ) -> Result<()> {
+        let channel_id = channel.get_id();
+
+        match channel {
+            Channel::Offered(_) => {
+                if db::dlc_channels::get_dlc_channel(conn, &channel_id)?.is_none() {
+                    let reference_id = channel
+                        .get_reference_id()
+                        .context("offered dlc channel is missing a reference id")?;
+                    let protocol_id = ProtocolId::try_from(reference_id)?;
+
+                    db::dlc_channels::insert_pending_dlc_channel(
+                        conn,
+                        &protocol_id,
+                        &channel_id,
+                        &to_secp_pk_30(channel.get_counter_party_id()),
+                    )?;
+
+                    tracing::warn!(?channel_id, "Backfilled missing pending dlc channel");
+                }
+            }
+            Channel::Signed(signed_channel) => {
+                let coordinator_reserve = self
+                    .inner
+                    .get_dlc_channel_usable_balance(&signed_channel.channel_id)?;
+                let trader_reserve = self
+                    .inner
+                    .get_dlc_channel_usable_balance_counterparty(&signed_channel.channel_id)?;
+
+                match db::dlc_channels::get_dlc_channel(conn, &channel_id)? {
+                    Some(existing)
+                        if existing.coordinator_reserve_sats == coordinator_reserve
+                            && existing.trader_reserve_sats == trader_reserve =>
+                    {
+                        // Already up to date.
+                    }
+                    Some(_) => {
+                        db::dlc_channels::update_channel(
+                            conn,
+                            &channel_id,
+                            coordinator_reserve,
+                            trader_reserve,
+                        )?;
+
+                        tracing::warn!(?channel_id, "Reconciled drifted dlc channel reserves");
+                    }
+                    None => {
+                        let reference_id = channel
+                            .get_reference_id()
+                            .context("signed dlc channel is missing a reference id")?;
+                        let protocol_id = ProtocolId::try_from(reference_id)?;
+
+                        let coordinator_funding =
+                            Amount::from_sat(signed_channel.own_params.collateral);
+                        let trader_funding =
+                            Amount::from_sat(signed_channel.counter_params.collateral);
+
+                        db::dlc_channels::set_dlc_channel_open(
+                            conn,
+                            &protocol_id,
+                            &channel_id,
+                            to_txid_30(signed_channel.fund_tx.txid()),
+                            coordinator_reserve,
+                            trader_reserve,
+                            coordinator_funding,
+                            trader_funding,
+                        )?;
+
+                        tracing::warn!(?channel_id, "Backfilled missing open dlc channel");
+                    }
+                }
+            }
+            Channel::Closing(ClosingChannel {
+                buffer_transaction, ..
+            }) => {
+                db::dlc_channels::set_channel_force_closing(
+                    conn,
+                    &channel_id,
+                    to_txid_30(buffer_transaction.txid()),
+                )?;
+            }
+            Channel::SettledClosing(SettledClosingChannel {
+                settle_transaction,
+                claim_transaction,
+                ..
+            }) => {
+                db::dlc_channels::set_channel_force_closing_settled(
+                    conn,
+                    &channel_id,
+                    to_txid_30(settle_transaction.txid()),
+                    Some(to_txid_30(claim_transaction.txid())),
+                )?;
+            }
+            Channel::ClosedPunished(ClosedPunishedChannel { punish_txid, .. }) => {
+                db::dlc_channels::set_channel_punished(conn, &channel_id, to_txid_30(*punish_txid))?;
+            }
+            Channel::Closed(ClosedChannel { closing_txid, .. })
+            | Channel::CounterClosed(ClosedChannel { closing_txid, .. })
+            | Channel::CollaborativelyClosed(ClosedChannel { closing_txid, .. }) => {
+                db::dlc_channels::set_channel_collab_closed(
+                    conn,
+                    &channel_id,
+                    to_txid_30(*closing_txid),
+                )?;
+            }
+            // Transient intermediate states and terminal failure states aren't independently
+            // reconciled: they either settle into one of the states above shortly, or they are
+            // already handled by `shadow_dlc_channel` without needing any recomputed data.
+            Channel::Accepted(_) | Channel::FailedAccept(_) | Channel::FailedSign(_) => {}
+        }
+
+        Ok(())
+    }
+
+    /// Checks whether a newly observed `Closing`/`SettledClosing` event's buffer/settle
+    /// transaction is a revoked broadcast, and punishes the counterparty if so.
+    ///
+    /// Must run before [`Self::shadow_dlc_channel`] processes the same event: that call
+    /// overwrites the channel's shadowed `buffer_txid`/`settle_txid` with whatever was just
+    /// broadcast, which is exactly what [`Self::handle_revoked_channel_broadcast`] needs to
+    /// compare the new broadcast against.
+    fn check_for_revoked_channel_broadcast(&self, dlc_channel_event: DlcChannelEvent) -> Result<()> {
+        let reference_id = match dlc_channel_event.get_reference_id() {
+            Some(reference_id) => reference_id,
+            None => return Ok(()),
+        };
+
+        let (channel_id, broadcast_txid) = match dlc_channel_event {
+            DlcChannelEvent::Closing(_) => {
+                let channel = self.inner.get_dlc_channel_by_reference_id(reference_id)?;
+
+                let buffer_transaction = match &channel {
+                    Channel::Signed(SignedChannel {
+                        state:
+                            SignedChannelState::Closing {
+                                buffer_transaction, ..
+                            },
+                        ..
+                    }) => buffer_transaction,
+                    Channel::Closing(ClosingChannel {
+                        buffer_transaction, ..
+                    }) => buffer_transaction,
+                    _ => return Ok(()),
+                };
+
+                (channel.get_id(), to_txid_30(buffer_transaction.txid()))
+            }
+            DlcChannelEvent::SettledClosing(_) => {
+                let channel = self.inner.get_dlc_channel_by_reference_id(reference_id)?;
+
+                let settle_transaction = match &channel {
+                    Channel::Signed(SignedChannel {
+                        state:
+                            SignedChannelState::SettledClosing {
+                                settle_transaction, ..
+                            },
+                        ..
+                    }) => settle_transaction,
+                    Channel::SettledClosing(SettledClosingChannel {
+                        settle_transaction, ..
+                    }) => settle_transaction,
+                    _ => return Ok(()),
+                };
+
+                (channel.get_id(), to_txid_30(settle_transaction.txid()))
+            }
+            // Every other event either doesn't broadcast a buffer/settle transaction, or (like
+            // `ClosedPunished`) is rust-dlc's own chain monitor already having detected and
+            // punished a revoked broadcast for a channel it still actively tracks.
+            _ => return Ok(()),
+        };
+
+        let mut conn = self.pool.get()?;
+        if db::dlc_channels::get_dlc_channel(&mut conn, &channel_id)?.is_none() {
+            // We haven't shadowed this channel yet; nothing to compare the broadcast against.
+            return Ok(());
+        }
+
+        self.handle_revoked_channel_broadcast(channel_id, broadcast_txid)
+    }
+
     pub fn shadow_dlc_channel(&self, dlc_channel_event: DlcChannelEvent) -> Result<()> {
         let mut conn = self.pool.get()?;
 
@@ -325,8 +798,27 @@ impl Node {
                 db::dlc_channels::set_channel_cancelled(&mut conn, &protocol_id)?;
             }
             DlcChannelEvent::Deleted(_) => {} // delete is handled above.
+            // Record which party made this settlement offer, so that a later revoked buffer/settle
+            // broadcast for this channel can be checked against it via
+            // `did_we_offer_last_channel_settlement`.
+            DlcChannelEvent::SettledOffered(_) => {
+                let is_offer = match channel {
+                    Channel::Signed(SignedChannel {
+                        state: SignedChannelState::SettledOffered { is_offer, .. },
+                        ..
+                    }) => *is_offer,
+                    channel => {
+                        bail!("DLC channel in unexpected state. dlc_channel = {channel:?}")
+                    }
+                };
+
+                db::dlc_channels::set_last_settlement_offerer(
+                    &mut conn,
+                    &channel.get_id(),
+                    is_offer,
+                )?;
+            }
             DlcChannelEvent::Accepted(_)
-            | DlcChannelEvent::SettledOffered(_)
             | DlcChannelEvent::SettledReceived(_)
             | DlcChannelEvent::SettledAccepted(_)
             | DlcChannelEvent::SettledConfirmed(_)
@@ -346,7 +838,9 @@ impl Node {
     /// (from the attestation and a trader realized pnl calculated from the cet payout and the
     /// last trader reserve)
     ///
-    /// If the dlc channel is `CollaborativelyClosed` we finish the corresponding dlc_protocol.
+    /// If the dlc channel is `CollaborativelyClosed` we finish the corresponding dlc_protocol;
+    /// unlike the other closing paths, this one is not deferred behind
+    /// [`CLOSING_TX_CONFIRMATION_THRESHOLD`] confirmations (see that constant's doc comment).
     async fn check_for_dlc_channel_closures(
         &self,
         dlc_channel_event: DlcChannelEvent,
@@ -395,7 +889,7 @@ impl Node {
                     format!("Couldn't find closing position for trader. trader_id = {trader_id}")
                 })?;
 
-                let (closing_price, trader_realized_pnl_sat) = match contract {
+                let (closing_price, trader_realized_pnl_sat, closing_txid) = match contract {
                     Contract::PreClosed(PreClosedContract {
                         // We assume a closed contract does always have an attestation
                         attestations: Some(attestations),
@@ -408,22 +902,18 @@ impl Node {
                         signed_cet: Some(signed_cet),
                         ..
                     }) => {
+                        let closing_txid = to_txid_30(signed_cet.txid());
+
                         let trader_realized_pnl_sat = self.calculate_trader_realized_pnl_from_cet(
                             &mut conn,
                             &dlc_protocol.channel_id,
                             signed_cet,
                         )?;
 
-                        let closing_price = Decimal::from_str_radix(
-                            &attestations
-                                .first()
-                                .context("at least one attestation")?
-                                .outcomes
-                                .join(""),
-                            2,
-                        )?;
+                        let closing_price =
+                            self.closing_price_from_attestations(contract_id, &attestations)?;
 
-                        (closing_price, trader_realized_pnl_sat)
+                        (closing_price, trader_realized_pnl_sat, closing_txid)
                     }
                     contract => {
                         bail!("Contract in unexpected state. Expected PreClosed or Closed Got: {:?}, trader_id = {trader_id}", contract)
@@ -436,18 +926,18 @@ impl Node {
                     "Finalize closing position after force closure",
                 );
 
-                if db::positions::Position::set_position_to_closed_with_pnl(
+                self.defer_position_close_until_confirmed(
                     &mut conn,
                     position.id,
+                    closing_txid,
                     trader_realized_pnl_sat,
                     closing_price,
-                )? > 0
-                {
-                    tracing::info!(%trader_id, "Set closing position to closed after the dlc channel got force closed.");
-                } else {
-                    tracing::warn!(%trader_id, "Failed to set closing position to closed after the dlc channel got force closed.");
-                }
+                )?;
             }
+            // A collaborative close finalizes through the dlc protocol executor's own position
+            // bookkeeping rather than `defer_position_close_until_confirmed`: that bookkeeping
+            // lives outside this module, so we can't safely re-route it through our
+            // confirmation-threshold mechanism without visibility into what it already does.
             DlcChannelEvent::CollaborativelyClosed(_) => {
                 let channel = &self.inner.get_dlc_channel_by_reference_id(reference_id)?;
                 let protocol_executor = dlc_protocol::DlcProtocolExecutor::new(self.pool.clone());
@@ -459,6 +949,56 @@ impl Node {
                     self.tx_position_feed.clone(),
                 )?;
             }
+            // The counterparty broadcast a revoked state and we punished them: they forfeit
+            // their entire channel balance to us.
+            DlcChannelEvent::ClosedPunished(_) => {
+                let channel = &self.inner.get_dlc_channel_by_reference_id(reference_id)?;
+                let trader_id = to_secp_pk_30(channel.get_counter_party_id());
+
+                self.finalize_position_as_punished(&mut conn, trader_id, &channel.get_id())?;
+            }
+            // The settle transaction from a unilateral close has now been claimed after its CSV
+            // delay; only at this point do we know the final payout and can finalize PnL.
+            DlcChannelEvent::SettledClosing(_) => {
+                let channel = &self.inner.get_dlc_channel_by_reference_id(reference_id)?;
+
+                let claim_transaction = match channel {
+                    Channel::SettledClosing(SettledClosingChannel {
+                        claim_transaction, ..
+                    }) => claim_transaction.clone(),
+                    // The settle transaction hasn't been claimed yet; we'll hear about this
+                    // channel again once it has.
+                    _ => return Ok(()),
+                };
+
+                let trader_id = to_secp_pk_30(channel.get_counter_party_id());
+
+                let position = db::positions::Position::get_position_by_trader(
+                    &mut conn,
+                    trader_id,
+                    vec![PositionState::Closing { closing_price: 0.0 }],
+                )?
+                .with_context(|| {
+                    format!("Couldn't find closing position for trader. trader_id = {trader_id}")
+                })?;
+
+                let claim_txid = to_txid_30(claim_transaction.txid());
+
+                let trader_realized_pnl_sat = self.calculate_trader_realized_pnl_from_cet(
+                    &mut conn,
+                    &channel.get_id(),
+                    claim_transaction,
+                )?;
+                let closing_price = position.average_entry_price;
+
+                self.defer_position_close_until_confirmed(
+                    &mut conn,
+                    position.id,
+                    claim_txid,
+                    trader_realized_pnl_sat,
+                    closing_price,
+                )?;
+            }
             DlcChannelEvent::Offered(_)
             | DlcChannelEvent::Accepted(_)
             | DlcChannelEvent::Established(_)
@@ -467,13 +1007,11 @@ impl Node {
             | DlcChannelEvent::SettledAccepted(_)
             | DlcChannelEvent::SettledConfirmed(_)
             | DlcChannelEvent::Settled(_)
-            | DlcChannelEvent::SettledClosing(_)
             | DlcChannelEvent::RenewOffered(_)
             | DlcChannelEvent::RenewAccepted(_)
             | DlcChannelEvent::RenewConfirmed(_)
             | DlcChannelEvent::RenewFinalized(_)
             | DlcChannelEvent::CollaborativeCloseOffered(_)
-            | DlcChannelEvent::ClosedPunished(_)
             | DlcChannelEvent::FailedAccept(_)
             | DlcChannelEvent::FailedSign(_)
             | DlcChannelEvent::Cancelled(_)
@@ -483,6 +1021,72 @@ impl Node {
         Ok(())
     }
 
+    /// Reconstructs the closing price from a numeric-outcome contract's oracle attestations.
+    ///
+    /// Each attestation's `outcomes` is a digit-decomposition of that oracle's observed price in
+    /// the contract's configured base, so we join the digits and parse them using that base
+    /// (rather than a hard-coded radix). For a multi-oracle contract, at least `threshold`
+    /// oracles must report a price within `price_tolerance` of each other, or we refuse to accept
+    /// any of them; independent oracles sampling the same feed essentially never agree to the
+    /// exact last digit, so we treat prices within the contract's declared tolerance as agreeing
+    /// rather than requiring byte-identical values.
+    fn closing_price_from_attestations(
+        &self,
+        contract_id: &ContractId,
+        attestations: &[OracleAttestation],
+    ) -> Result<Decimal> {
+        let (base, threshold, price_tolerance) =
+            self.inner.get_contract_numeric_params(contract_id)?;
+
+        let reported_prices = attestations
+            .iter()
+            .map(|attestation| {
+                Decimal::from_str_radix(&attestation.outcomes.join(""), base).with_context(|| {
+                    format!(
+                        "Failed to parse oracle outcome as a base-{base} number. outcomes = {:?}",
+                        attestation.outcomes
+                    )
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        reconcile_oracle_prices(reported_prices, threshold, price_tolerance)
+    }
+
+    /// Records `closing_txid` as the pending close of `position_id`, deferring finalization
+    /// until [`Self::reconcile_closing_tx_confirmations`] sees it reach
+    /// [`CLOSING_TX_CONFIRMATION_THRESHOLD`] confirmations.
+    ///
+    /// We don't finalize a position the moment we see its closing transaction (a CET,
+    /// settle/claim, or punish transaction), because a chain reorg can
+    /// still evict it; finalizing eagerly would leave the DB reporting a realized PnL that never
+    /// actually happened.
+    fn defer_position_close_until_confirmed(
+        &self,
+        conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+        position_id: i32,
+        closing_txid: Txid,
+        trader_realized_pnl_sat: i64,
+        closing_price: Decimal,
+    ) -> Result<()> {
+        db::positions::Position::set_pending_closing_tx(
+            conn,
+            position_id,
+            closing_txid,
+            trader_realized_pnl_sat,
+            closing_price,
+            CLOSING_TX_CONFIRMATION_THRESHOLD,
+        )?;
+
+        tracing::info!(
+            position_id,
+            %closing_txid,
+            "Deferring position finalization until the closing transaction is reorg-safe"
+        );
+
+        Ok(())
+    }
+
     /// Calculates the trader realized pnl from the cet outputs which do not belong to us.
     /// 1. Sum the trader payouts
     /// 2. Subtract the trader reserve sats from the trader payout
@@ -513,4 +1117,345 @@ impl Node {
 
         Ok(trader_realized_pnl_sat)
     }
+
+    /// Closes `trader_id`'s position after a punishment: the trader forfeits their entire
+    /// channel reserve to us, and there is no CET attestation to derive a closing price from, so
+    /// we reuse the position's entry price.
+    fn finalize_position_as_punished(
+        &self,
+        conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+        trader_id: PublicKey,
+        channel_id: &DlcChannelId,
+    ) -> Result<()> {
+        let position = db::positions::Position::get_position_by_trader(
+            conn,
+            trader_id,
+            vec![PositionState::Closing { closing_price: 0.0 }],
+        )?
+        .with_context(|| {
+            format!("Couldn't find closing position for trader. trader_id = {trader_id}")
+        })?;
+
+        let dlc_channel = db::dlc_channels::get_dlc_channel(conn, channel_id)?
+            .with_context(|| format!("Couldn't find dlc channel by channel id = {channel_id:?}"))?;
+        let trader_realized_pnl_sat =
+            punishment_realized_pnl_sat(dlc_channel.trader_reserve_sats.to_sat());
+        let closing_price = position.average_entry_price;
+
+        let punish_txid = dlc_channel.punish_txid.with_context(|| {
+            format!("Missing punish txid for punished dlc channel. channel_id = {channel_id:?}")
+        })?;
+
+        self.defer_position_close_until_confirmed(
+            conn,
+            position.id,
+            punish_txid,
+            trader_realized_pnl_sat,
+            closing_price,
+        )?;
+
+        Ok(())
+    }
+
+    /// Looks up whether *we* (the coordinator) made the last settlement offer recorded for
+    /// `channel_id`, as tagged by [`Self::shadow_dlc_channel`] whenever a `SettledOffered` event
+    /// comes in; a `Settled` event does not update it.
+    ///
+    /// Returns `None` if no `SettledOffered` event has been recorded for the channel, in which
+    /// case [`Self::handle_revoked_channel_broadcast`] does not punish a mismatching broadcast.
+    pub fn did_we_offer_last_channel_settlement(
+        &self,
+        channel_id: &DlcChannelId,
+    ) -> Result<Option<bool>> {
+        let mut conn = self.pool.get()?;
+
+        db::dlc_channels::get_last_settlement_offerer(&mut conn, channel_id)
+    }
+
+    /// Detects and punishes a revoked buffer/settle transaction broadcast for `channel_id`.
+    ///
+    /// The broadcast transaction alone does not tell us whether it corresponds to the channel's
+    /// latest agreed state or a stale, revoked one, so we compare `broadcast_txid` against the
+    /// last buffer/settle txid we shadowed for this channel, corroborated by
+    /// [`Self::did_we_offer_last_channel_settlement`]: if the other party is the one who is
+    /// supposed to hold the latest state (i.e. *we* made the last settlement offer, so *they*
+    /// hold the signed counter-signature) and the broadcast doesn't match what we believe is
+    /// current, it is almost certainly an old, revoked state being rebroadcast.
+    pub fn handle_revoked_channel_broadcast(
+        &self,
+        channel_id: DlcChannelId,
+        broadcast_txid: Txid,
+    ) -> Result<()> {
+        let mut conn = self.pool.get()?;
+
+        let dlc_channel = db::dlc_channels::get_dlc_channel(&mut conn, &channel_id)?
+            .with_context(|| format!("Couldn't find dlc channel by channel id = {channel_id:?}"))?;
+
+        let is_latest_known_state = dlc_channel.buffer_txid == Some(broadcast_txid)
+            || dlc_channel.settle_txid == Some(broadcast_txid);
+
+        if is_latest_known_state {
+            // This matches the state we already believe is current; nothing revoked here.
+            return Ok(());
+        }
+
+        if dlc_channel.buffer_txid.is_none() && dlc_channel.settle_txid.is_none() {
+            // We have never shadowed a buffer/settle txid for this channel before, so there is no
+            // prior state for `broadcast_txid` to contradict; this is the channel's first
+            // unilateral-close broadcast, not a stale one being replayed.
+            return Ok(());
+        }
+
+        let we_offered_last_settlement =
+            db::dlc_channels::get_last_settlement_offerer(&mut conn, &channel_id)?;
+
+        if !should_punish_mismatched_broadcast(we_offered_last_settlement) {
+            // We only hold provable evidence of a revoked state when *we* made the last
+            // settlement offer (so the counterparty holds the latest signed counter-state). If we
+            // don't know who offered last, or the counterparty did, a mismatching broadcast is
+            // just as likely to mean our own shadow state is behind; punishing here could forfeit
+            // an honest counterparty's channel balance.
+            tracing::warn!(
+                ?channel_id,
+                %broadcast_txid,
+                ?we_offered_last_settlement,
+                "Detected a buffer/settle transaction that doesn't match the channel's latest \
+                 known state, but we don't hold provable evidence that it is revoked; not \
+                 punishing"
+            );
+
+            return Ok(());
+        }
+
+        tracing::warn!(
+            ?channel_id,
+            %broadcast_txid,
+            "Detected a buffer/settle transaction that doesn't match the channel's latest known \
+             state; treating it as a revoked broadcast and punishing the counterparty"
+        );
+
+        let punish_txid = self
+            .inner
+            .punish_dlc_channel(&channel_id, broadcast_txid)
+            .context("Failed to punish revoked dlc channel broadcast")?;
+
+        db::dlc_channels::set_channel_punished(&mut conn, &channel_id, punish_txid)?;
+
+        let trader_id = dlc_channel.trader;
+        self.finalize_position_as_punished(&mut conn, trader_id, &channel_id)?;
+
+        Ok(())
+    }
+}
+
+/// What to do with a position pending closing-tx confirmation, given [`pending_closing_tx_outcome`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PendingClosingTxOutcome {
+    /// The closing transaction hasn't reached the required depth yet; check again next time.
+    StillPending,
+    /// The closing transaction is buried deep enough to be considered reorg-safe.
+    Finalize,
+    /// The closing transaction has disappeared from the chain, most likely evicted by a reorg.
+    Revert,
+}
+
+/// Decides what to do with a position pending closing-tx confirmation, given the transaction's
+/// current confirmation depth (`None` if it's no longer visible on chain at all).
+fn pending_closing_tx_outcome(
+    confirmations: Option<u32>,
+    required_confirmations: u32,
+) -> PendingClosingTxOutcome {
+    match confirmations {
+        Some(confirmations) if confirmations >= required_confirmations => {
+            PendingClosingTxOutcome::Finalize
+        }
+        Some(_) => PendingClosingTxOutcome::StillPending,
+        None => PendingClosingTxOutcome::Revert,
+    }
+}
+
+/// Finds a closing price that at least `threshold` of `reported_prices` agree on, within
+/// `price_tolerance` of each other, and returns the average of that agreeing group.
+///
+/// Independent oracles sampling the same feed essentially never agree to the exact last digit, so
+/// prices within `price_tolerance` are treated as agreeing rather than requiring byte-identical
+/// values.
+fn reconcile_oracle_prices(
+    mut reported_prices: Vec<Decimal>,
+    threshold: usize,
+    price_tolerance: Decimal,
+) -> Result<Decimal> {
+    reported_prices.sort();
+
+    let threshold = threshold.max(1);
+
+    reported_prices
+        .windows(threshold)
+        .find(|window| match (window.first(), window.last()) {
+            (Some(lowest), Some(highest)) => highest - lowest <= price_tolerance,
+            _ => false,
+        })
+        .map(|window| window.iter().sum::<Decimal>() / Decimal::from(window.len()))
+        .with_context(|| {
+            format!(
+                "Fewer than {threshold} out of {} oracles agreed (within {price_tolerance}) on a \
+                 closing price. reported_prices = {reported_prices:?}",
+                reported_prices.len()
+            )
+        })
+}
+
+/// Whether a buffer/settle broadcast that doesn't match our shadowed latest channel state should
+/// be treated as a revoked, punishable broadcast.
+///
+/// We only hold provable evidence of revocation when *we* made the last settlement offer (so the
+/// counterparty holds the latest signed counter-state); if we don't know who offered last, or the
+/// counterparty did, a mismatch is just as likely to mean our own shadow state is behind.
+fn should_punish_mismatched_broadcast(we_offered_last_settlement: Option<bool>) -> bool {
+    we_offered_last_settlement == Some(true)
+}
+
+/// The trader's realized PnL when their channel reserve is forfeited to us as punishment: always
+/// the full negative of their reserve, since a punished channel has no CET payout to offset it.
+fn punishment_realized_pnl_sat(trader_reserve_sats: u64) -> i64 {
+    -(trader_reserve_sats as i64)
+}
+
+/// Channel ids present in `shadow_channel_ids` but absent from `seen_channel_ids` — i.e. rows
+/// whose underlying dlc channel no longer exists in rust-dlc's own store.
+fn orphaned_channel_ids(
+    shadow_channel_ids: impl IntoIterator<Item = DlcChannelId>,
+    seen_channel_ids: &HashSet<DlcChannelId>,
+) -> Vec<DlcChannelId> {
+    shadow_channel_ids
+        .into_iter()
+        .filter(|channel_id| !seen_channel_ids.contains(channel_id))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn channel_id(byte: u8) -> DlcChannelId {
+        [byte; 32]
+    }
+
+    #[test]
+    fn orphaned_channel_ids_keeps_only_ids_missing_from_seen() {
+        let seen = HashSet::from([channel_id(1), channel_id(2)]);
+
+        let orphaned = orphaned_channel_ids([channel_id(1), channel_id(2), channel_id(3)], &seen);
+
+        assert_eq!(orphaned, vec![channel_id(3)]);
+    }
+
+    #[test]
+    fn orphaned_channel_ids_is_empty_when_everything_is_seen() {
+        let seen = HashSet::from([channel_id(1), channel_id(2)]);
+
+        let orphaned = orphaned_channel_ids([channel_id(1), channel_id(2)], &seen);
+
+        assert!(orphaned.is_empty());
+    }
+
+    #[test]
+    fn punishment_realized_pnl_is_the_full_negative_reserve() {
+        assert_eq!(punishment_realized_pnl_sat(0), 0);
+        assert_eq!(punishment_realized_pnl_sat(50_000), -50_000);
+    }
+
+    #[test]
+    fn only_punishes_when_we_provably_offered_the_last_settlement() {
+        assert!(should_punish_mismatched_broadcast(Some(true)));
+        assert!(!should_punish_mismatched_broadcast(Some(false)));
+        assert!(!should_punish_mismatched_broadcast(None));
+    }
+
+    #[test]
+    fn reconcile_oracle_prices_averages_an_exactly_agreeing_window() {
+        let prices = vec![Decimal::from(50_000), Decimal::from(50_000), Decimal::from(50_000)];
+
+        let closing_price = reconcile_oracle_prices(prices, 3, Decimal::ZERO).unwrap();
+
+        assert_eq!(closing_price, Decimal::from(50_000));
+    }
+
+    #[test]
+    fn reconcile_oracle_prices_accepts_prices_within_tolerance() {
+        let prices = vec![
+            Decimal::from(49_999),
+            Decimal::from(50_000),
+            Decimal::from(50_002),
+        ];
+
+        let closing_price = reconcile_oracle_prices(prices, 3, Decimal::from(5)).unwrap();
+
+        // Average of the agreeing window, not the first or last value.
+        assert_eq!(closing_price, Decimal::from(50_000) + Decimal::ONE / Decimal::from(3));
+    }
+
+    #[test]
+    fn reconcile_oracle_prices_picks_the_smallest_agreeing_window_when_threshold_is_partial() {
+        let prices = vec![
+            Decimal::from(10_000),
+            Decimal::from(50_000),
+            Decimal::from(50_001),
+            Decimal::from(90_000),
+        ];
+
+        let closing_price = reconcile_oracle_prices(prices, 2, Decimal::ONE).unwrap();
+
+        assert_eq!(closing_price, Decimal::from(50_000) + Decimal::ONE / Decimal::from(2));
+    }
+
+    #[test]
+    fn reconcile_oracle_prices_fails_when_fewer_than_threshold_agree() {
+        let prices = vec![
+            Decimal::from(10_000),
+            Decimal::from(50_000),
+            Decimal::from(90_000),
+        ];
+
+        let result = reconcile_oracle_prices(prices, 2, Decimal::ONE);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn reconcile_oracle_prices_treats_a_single_oracle_as_always_agreeing() {
+        let prices = vec![Decimal::from(50_000)];
+
+        let closing_price = reconcile_oracle_prices(prices, 1, Decimal::ZERO).unwrap();
+
+        assert_eq!(closing_price, Decimal::from(50_000));
+    }
+
+    #[test]
+    fn pending_closing_tx_stays_pending_below_the_required_depth() {
+        assert_eq!(
+            pending_closing_tx_outcome(Some(1), 6),
+            PendingClosingTxOutcome::StillPending
+        );
+    }
+
+    #[test]
+    fn pending_closing_tx_finalizes_once_deep_enough() {
+        assert_eq!(
+            pending_closing_tx_outcome(Some(6), 6),
+            PendingClosingTxOutcome::Finalize
+        );
+        assert_eq!(
+            pending_closing_tx_outcome(Some(10), 6),
+            PendingClosingTxOutcome::Finalize
+        );
+    }
+
+    #[test]
+    fn pending_closing_tx_reverts_when_it_disappears() {
+        assert_eq!(
+            pending_closing_tx_outcome(None, 6),
+            PendingClosingTxOutcome::Revert
+        );
+    }
 }
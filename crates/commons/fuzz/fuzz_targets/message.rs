@@ -0,0 +1,19 @@
+#![no_main]
+
+use commons::Message;
+use libfuzzer_sys::fuzz_target;
+
+// `Message` is the orderbook's own wire format; it is deserialized straight from bytes received
+// over a websocket connection, so it must never panic on malformed input. We also round-trip
+// whatever successfully deserializes, to make sure `Serialize`/`Deserialize` agree with each
+// other.
+fuzz_target!(|data: &[u8]| {
+    let Ok(message) = serde_json::from_slice::<Message>(data) else {
+        return;
+    };
+
+    let bytes = serde_json::to_vec(&message).expect("a deserialized Message to re-serialize");
+
+    serde_json::from_slice::<Message>(&bytes)
+        .expect("a re-serialized Message to deserialize again");
+});
@@ -0,0 +1,18 @@
+#![no_main]
+
+use commons::OrderbookRequest;
+use libfuzzer_sys::fuzz_target;
+
+// Same rationale as the `message` target: `OrderbookRequest` is deserialized from
+// client-controlled bytes before authentication has even happened, so it is a prime target for
+// malformed-input fuzzing.
+fuzz_target!(|data: &[u8]| {
+    let Ok(request) = serde_json::from_slice::<OrderbookRequest>(data) else {
+        return;
+    };
+
+    let bytes = serde_json::to_vec(&request).expect("a deserialized OrderbookRequest to re-serialize");
+
+    serde_json::from_slice::<OrderbookRequest>(&bytes)
+        .expect("a re-serialized OrderbookRequest to deserialize again");
+});
@@ -0,0 +1,243 @@
+use crate::dlc_custom_signer::CustomKeysManager;
+use crate::fee_rate_estimator::FeeRateEstimator;
+use crate::ln::TracingLogger;
+use crate::ln_dlc_wallet::LnDlcWallet;
+use crate::storage::TenTenOneStorage;
+use crate::ChannelManager;
+use crate::Storage;
+use anyhow::anyhow;
+use anyhow::Result;
+use bitcoin::OutPoint;
+use bitcoin::Transaction;
+use lightning::chain::chaininterface::ConfirmationTarget;
+use lightning::events::bump_transaction::BumpTransactionEvent;
+use lightning::events::bump_transaction::CoinSelection;
+use lightning::events::bump_transaction::CoinSelectionSource;
+use lightning::events::bump_transaction::Input;
+use lightning::events::bump_transaction::Utxo;
+use lightning::events::bump_transaction::WalletSource;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// Fee bumper for anchor-output channels.
+///
+/// Wraps LDK's [`lightning::events::bump_transaction::BumpTransactionEventHandler`] with a
+/// [`CoinSelectionSource`] backed by the on-chain BDK wallet and a signer backed by the
+/// [`CustomKeysManager`]. A [`BumpTransactionEvent`] reaches it through
+/// [`super::Node::handle_bump_transaction_event`]; it is not subscribed to
+/// [`super::event::NodeEventHandler`].
+pub struct BumpTxEventHandler<S: TenTenOneStorage, N: Storage> {
+    inner: Arc<
+        lightning::events::bump_transaction::BumpTransactionEventHandler<
+            Arc<LnDlcWallet<S, N>>,
+            Arc<LnDlcCoinSelectionSource<S, N>>,
+            Arc<CustomKeysManager<S, N>>,
+            Arc<TracingLogger>,
+        >,
+    >,
+}
+
+impl<S: TenTenOneStorage + 'static, N: Storage + Sync + Send + 'static> BumpTxEventHandler<S, N> {
+    pub fn new(
+        wallet: Arc<LnDlcWallet<S, N>>,
+        fee_rate_estimator: Arc<FeeRateEstimator>,
+        keys_manager: Arc<CustomKeysManager<S, N>>,
+        logger: Arc<TracingLogger>,
+        anchor_bump_utxo_reserve: usize,
+    ) -> Self {
+        let coin_selection_source = Arc::new(LnDlcCoinSelectionSource::new(
+            wallet.clone(),
+            fee_rate_estimator,
+            anchor_bump_utxo_reserve,
+        ));
+
+        let inner = Arc::new(
+            lightning::events::bump_transaction::BumpTransactionEventHandler::new(
+                wallet,
+                coin_selection_source,
+                keys_manager,
+                logger,
+            ),
+        );
+
+        Self { inner }
+    }
+
+    pub fn handle_event(&self, event: &BumpTransactionEvent) {
+        self.inner.handle_event(event);
+    }
+}
+
+/// A set of outpoints reserved between coin selection and signing, so that two concurrent
+/// `BumpTransaction` events never select the same wallet UTXO while both are being built. A
+/// reservation ends once the fee-bump PSBT is signed (or fails to sign); after that the UTXO can
+/// be selected again, including by a retry of the same claim.
+#[derive(Default)]
+struct LockedUtxos {
+    locked: parking_lot::Mutex<HashSet<OutPoint>>,
+}
+
+impl LockedUtxos {
+    fn lock(&self, outpoints: impl Iterator<Item = OutPoint>) {
+        self.locked.lock().extend(outpoints);
+    }
+
+    fn unlock(&self, outpoints: impl Iterator<Item = OutPoint>) {
+        let mut locked = self.locked.lock();
+        for outpoint in outpoints {
+            locked.remove(&outpoint);
+        }
+    }
+
+    fn is_locked(&self, outpoint: &OutPoint) -> bool {
+        self.locked.lock().contains(outpoint)
+    }
+}
+
+/// [`CoinSelectionSource`] implementation used to fee-bump anchor-channel commitment and HTLC
+/// transactions via CPFP.
+///
+/// Confirmed UTXOs are drawn from the on-chain BDK wallet; UTXOs reserved by another bump that
+/// has not been signed yet are skipped (see [`LockedUtxos`]).
+pub struct LnDlcCoinSelectionSource<S: TenTenOneStorage, N: Storage> {
+    wallet: Arc<LnDlcWallet<S, N>>,
+    fee_rate_estimator: Arc<FeeRateEstimator>,
+    locked_utxos: LockedUtxos,
+    /// Minimum number of confirmed UTXOs we try to always keep available for a future anchor
+    /// bump, so that one large CPFP doesn't starve the next one.
+    anchor_bump_utxo_reserve: usize,
+}
+
+impl<S: TenTenOneStorage, N: Storage> LnDlcCoinSelectionSource<S, N> {
+    fn new(
+        wallet: Arc<LnDlcWallet<S, N>>,
+        fee_rate_estimator: Arc<FeeRateEstimator>,
+        anchor_bump_utxo_reserve: usize,
+    ) -> Self {
+        Self {
+            wallet,
+            fee_rate_estimator,
+            locked_utxos: LockedUtxos::default(),
+            anchor_bump_utxo_reserve,
+        }
+    }
+
+    fn confirmed_utxos(&self) -> Result<Vec<Utxo>> {
+        self.wallet
+            .ldk_wallet()
+            .list_confirmed_utxos()
+            .map_err(|e| anyhow!("Failed to list confirmed UTXOs: {e:#}"))
+    }
+}
+
+impl<S: TenTenOneStorage, N: Storage> CoinSelectionSource for LnDlcCoinSelectionSource<S, N> {
+    fn select_confirmed_utxos(
+        &self,
+        claim_id: lightning::events::bump_transaction::ClaimId,
+        must_spend: Vec<Input>,
+        must_pay_to: &[bitcoin::TxOut],
+        target_feerate_sat_per_1000_weight: u32,
+    ) -> Result<CoinSelection, ()> {
+        // Never touch the transaction's own (anchor/HTLC) inputs; they are already fixed by the
+        // `must_spend` set.
+        let reserved: HashSet<OutPoint> = must_spend.iter().map(|input| input.outpoint).collect();
+
+        let confirmed_utxos = self.confirmed_utxos().map_err(|e| {
+            tracing::error!("Failed to source UTXOs for fee bump: {e:#}");
+        })?;
+
+        let mut available_utxos = confirmed_utxos
+            .into_iter()
+            .filter(|utxo| {
+                !reserved.contains(&utxo.outpoint) && !self.locked_utxos.is_locked(&utxo.outpoint)
+            })
+            .collect::<Vec<_>>();
+
+        // Keep the smallest `anchor_bump_utxo_reserve` confirmed UTXOs out of this selection, so
+        // that a later, concurrent bump (e.g. for a different channel) still has inputs to draw
+        // from. If we don't have enough spare UTXOs to honour the reserve, fall back to using
+        // everything rather than failing the bump outright.
+        if available_utxos.len() > self.anchor_bump_utxo_reserve {
+            available_utxos.sort_by_key(|utxo| utxo.output.value);
+            available_utxos.drain(..self.anchor_bump_utxo_reserve);
+        } else if !available_utxos.is_empty() {
+            tracing::warn!(
+                available = available_utxos.len(),
+                reserve = self.anchor_bump_utxo_reserve,
+                "Anchor-bump UTXO reserve exhausted, using all confirmed UTXOs"
+            );
+        }
+
+        // LDK recomputes the required feerate on every retry, so we simply hand it the current
+        // high-priority estimate as a floor and let it pick enough UTXOs to reach
+        // `target_feerate_sat_per_1000_weight`.
+        let high_priority_feerate = self
+            .fee_rate_estimator
+            .get(ConfirmationTarget::UrgentOnChainSweep);
+        let target_feerate_sat_per_1000_weight =
+            target_feerate_sat_per_1000_weight.max(high_priority_feerate.fee_wu(1000) as u32);
+        tracing::debug!(
+            ?claim_id,
+            target_feerate_sat_per_1000_weight,
+            high_priority_feerate = high_priority_feerate.fee_wu(1000),
+            "Selecting UTXOs for anchor-channel fee bump"
+        );
+
+        let selection = self
+            .wallet
+            .ldk_wallet()
+            .select_coins_for_bump(
+                must_spend,
+                must_pay_to,
+                target_feerate_sat_per_1000_weight,
+                available_utxos,
+            )
+            .map_err(|e| {
+                tracing::error!("Failed to select coins for fee bump: {e:#}");
+            })?;
+
+        self.locked_utxos
+            .lock(selection.confirmed_utxos.iter().map(|utxo| utxo.outpoint));
+
+        Ok(selection)
+    }
+
+    fn sign_psbt(&self, psbt: bitcoin::psbt::PartiallySignedTransaction) -> Result<Transaction, ()> {
+        let outpoints = psbt
+            .unsigned_tx
+            .input
+            .iter()
+            .map(|input| input.previous_output)
+            .collect::<Vec<_>>();
+
+        let signed = self.wallet.ldk_wallet().sign_psbt(psbt).map_err(|e| {
+            tracing::error!("Failed to sign fee-bump transaction: {e:#}");
+        });
+
+        // Whether signing succeeded or not, the outpoints are no longer in flight once we're done
+        // with this attempt; a future retry will reselect (and relock) as needed.
+        self.locked_utxos.unlock(outpoints.into_iter());
+
+        signed
+    }
+}
+
+impl<S: TenTenOneStorage, N: Storage> WalletSource for LnDlcWallet<S, N> {
+    fn list_confirmed_utxos(&self) -> Result<Vec<Utxo>, ()> {
+        self.ldk_wallet().list_confirmed_utxos().map_err(|e| {
+            tracing::error!("Failed to list confirmed UTXOs: {e:#}");
+        })
+    }
+
+    fn get_change_script(&self) -> Result<bitcoin::ScriptBuf, ()> {
+        self.ldk_wallet().get_change_script().map_err(|e| {
+            tracing::error!("Failed to get change script: {e:#}");
+        })
+    }
+
+    fn sign_psbt(&self, psbt: bitcoin::psbt::PartiallySignedTransaction) -> Result<Transaction, ()> {
+        self.ldk_wallet().sign_psbt(psbt).map_err(|e| {
+            tracing::error!("Failed to sign PSBT: {e:#}");
+        })
+    }
+}
@@ -37,7 +37,6 @@ use lightning::chain::chaininterface::ConfirmationTarget;
 use lightning::chain::chainmonitor;
 use lightning::chain::Confirm;
 use lightning::ln::msgs::RoutingMessageHandler;
-use lightning::ln::peer_handler::IgnoringMessageHandler;
 use lightning::ln::peer_handler::MessageHandler;
 use lightning::routing::router::DefaultRouter;
 use lightning::routing::scoring::ProbabilisticScorer;
@@ -61,6 +60,7 @@ use std::fmt::Formatter;
 use std::io::BufReader;
 use std::net::SocketAddr;
 use std::path::Path;
+use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
 use std::time::Instant;
@@ -68,11 +68,15 @@ use std::time::SystemTime;
 use tokio::sync::RwLock;
 use tokio::task::spawn_blocking;
 
+mod bump_tx;
 mod channel_manager;
 mod connection;
 mod dlc_manager;
 mod ln_channel;
 mod oracle;
+mod payment_store;
+mod peer_store;
+mod spendable_outputs;
 mod storage;
 mod sub_channel_manager;
 mod wallet;
@@ -84,11 +88,17 @@ pub mod dlc_channel;
 pub mod event;
 pub mod peer_manager;
 
+pub use crate::node::bump_tx::BumpTxEventHandler;
 pub use crate::node::connection::TenTenOneOnionMessageHandler;
 pub use crate::node::dlc_manager::signed_channel_state_name;
 pub use crate::node::dlc_manager::DlcManager;
 use crate::node::event::NodeEventHandler;
 pub use crate::node::oracle::OracleInfo;
+pub use crate::node::payment_store::PaymentStore;
+pub use crate::node::peer_store::PeerStore;
+pub use crate::node::spendable_outputs::SpendableOutputSweepSettings;
+use crate::node::spendable_outputs::sweep_spendable_outputs_to_cold_storage;
+use lightning::events::bump_transaction::BumpTransactionEvent;
 pub use ::dlc_manager as rust_dlc_manager;
 pub use channel_manager::ChannelManager;
 pub use invoice::HTLCStatus;
@@ -97,7 +107,11 @@ use lightning::util::persist::KVStore;
 use lightning::util::persist::NETWORK_GRAPH_PERSISTENCE_KEY;
 use lightning::util::persist::NETWORK_GRAPH_PERSISTENCE_PRIMARY_NAMESPACE;
 use lightning::util::persist::NETWORK_GRAPH_PERSISTENCE_SECONDARY_NAMESPACE;
+use lightning::util::persist::SCORER_PERSISTENCE_KEY;
+use lightning::util::persist::SCORER_PERSISTENCE_PRIMARY_NAMESPACE;
+use lightning::util::persist::SCORER_PERSISTENCE_SECONDARY_NAMESPACE;
 use lightning::util::ser::ReadableArgs;
+use lightning::util::ser::Writeable;
 pub use storage::InMemoryStore;
 pub use storage::Storage;
 pub use sub_channel::dlc_message_name;
@@ -119,6 +133,21 @@ const MANAGE_SPENDABLE_OUTPUTS_INTERVAL: Duration = Duration::from_secs(30 * 60)
 /// Value taken from `ldk-node` project.
 const RGS_SYNC_INTERVAL: Duration = Duration::from_secs(60 * 60);
 
+/// Base delay used to back off `RGS_SYNC_INTERVAL` after a failed RGS snapshot fetch.
+const RGS_FAILURE_BACKOFF_BASE: Duration = Duration::from_secs(30);
+/// Upper bound on the backed-off retry delay, so a persistently unreachable RGS server never
+/// leaves us waiting longer than this between attempts.
+const RGS_FAILURE_BACKOFF_MAX: Duration = Duration::from_secs(30 * 60);
+/// Number of consecutive RGS failures after which we consider the snapshot source degraded. We
+/// always keep a passive [`P2pGossipSync`] wired up as the peer manager's routing message
+/// handler, so the network graph keeps receiving live gossip through connected peers regardless;
+/// this threshold only gates the warning that tells us RGS itself is unhealthy.
+const RGS_DEGRADED_FAILURE_THRESHOLD: u32 = 3;
+
+/// The interval at which we diff the persisted peer set against the peers we are currently
+/// connected to and re-dial anyone who has dropped off.
+const RECONNECT_TO_PEERS_INTERVAL: Duration = Duration::from_secs(60);
+
 type Scorer = ProbabilisticScorer<Arc<NetworkGraph>, Arc<TracingLogger>>;
 
 type NodeEsploraClient = EsploraSyncClient<Arc<TracingLogger>>;
@@ -170,6 +199,17 @@ pub struct Node<S: TenTenOneStorage, N: Storage> {
 
     pub event_handler: Arc<NodeEventHandler>,
 
+    /// Services [`lightning::events::Event::BumpTransaction`] events so that anchor-channel
+    /// commitment and HTLC transactions get fee-bumped via CPFP.
+    pub bump_tx_event_handler: Arc<BumpTxEventHandler<S, N>>,
+
+    /// Peers recorded from inbound connections, so we can re-dial them on restart; outbound
+    /// connections are not recorded (see [`PeerStore`]).
+    pub peer_store: Arc<PeerStore<S>>,
+
+    /// Durable record of inbound and outbound Lightning payments.
+    pub payment_store: Arc<PaymentStore<S>>,
+
     // storage
     // TODO(holzeis): The node storage should get extracted to the corresponding application
     // layers.
@@ -246,6 +286,25 @@ pub struct LnDlcNodeSettings {
 
     /// XXX: Requires restart of the node to take effect
     pub gossip_source_config: GossipSourceConfig,
+
+    /// When set, spendable outputs (channel-close and HTLC-claim outputs) are periodically swept
+    /// straight to cold storage instead of being recycled into the node's hot on-chain wallet.
+    pub spendable_output_sweep: Option<SpendableOutputSweepSettings>,
+
+    /// Minimum number of confirmed on-chain UTXOs the anchor-channel fee bumper tries to always
+    /// keep available, so that one CPFP doesn't starve a concurrent bump on another channel.
+    ///
+    /// XXX: Requires restart of the node to take effect
+    pub anchor_bump_utxo_reserve: usize,
+
+    /// How often we persist the [`Scorer`]. No decay is applied by this task; the scorer's
+    /// liquidity estimates decay lazily as they are read.
+    ///
+    /// This runs independently of [`lightning_background_processor::process_events_async`]'s own
+    /// persistence timer, so the scorer's learned liquidity estimates survive even if the
+    /// background processor is stuck handling a slow event.
+    #[serde_as(as = "DurationSeconds")]
+    pub scorer_persist_interval: Duration,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
@@ -291,6 +350,12 @@ impl<S: TenTenOneStorage + 'static, N: Storage + Sync + Send + 'static> Node<S,
             alias: alias.to_string(),
         });
 
+        let mut ldk_config = ldk_config;
+        // Anchor outputs let us fee-bump commitment and HTLC transactions via CPFP instead of
+        // relying solely on the feerate negotiated at channel-open time.
+        ldk_config
+            .channel_handshake_config
+            .negotiate_anchors_zero_fee_htlc_tx = true;
         let ldk_config = Arc::new(parking_lot::RwLock::new(ldk_config));
 
         let on_chain_dir = data_dir.join("on_chain");
@@ -338,6 +403,16 @@ impl<S: TenTenOneStorage + 'static, N: Storage + Sync + Send + 'static> Node<S,
             ))
         };
 
+        let peer_store = Arc::new(PeerStore::new(ln_storage.clone()));
+
+        let bump_tx_event_handler = Arc::new(BumpTxEventHandler::new(
+            ln_dlc_wallet.clone(),
+            fee_rate_estimator.clone(),
+            keys_manager.clone(),
+            logger.clone(),
+            settings.anchor_bump_utxo_reserve,
+        ));
+
         let network_graph = match KVStore::read(
             ln_storage.as_ref(),
             NETWORK_GRAPH_PERSISTENCE_PRIMARY_NAMESPACE,
@@ -395,6 +470,11 @@ impl<S: TenTenOneStorage + 'static, N: Storage + Sync + Send + 'static> Node<S,
 
         let channel_manager = Arc::new(channel_manager);
 
+        let payment_store = Arc::new(PaymentStore::new(ln_storage.clone()));
+        if let Err(e) = payment_store.reconcile_with_channel_manager(&channel_manager) {
+            tracing::warn!("Failed to reconcile payment store on startup. Error: {e:#}");
+        }
+
         let gossip_source = match &settings.gossip_source_config {
             GossipSourceConfig::P2pNetwork => {
                 let gossip_sync = Arc::new(P2pGossipSync::new(
@@ -437,14 +517,19 @@ impl<S: TenTenOneStorage + 'static, N: Storage + Sync + Send + 'static> Node<S,
 
         let dlc_message_handler = Arc::new(DlcMessageHandler::new());
 
+        // Even when RGS is the configured primary source, we still register a P2P gossip sync as
+        // the peer manager's routing message handler. This way, gossip announcements from
+        // connected peers keep flowing into the shared `network_graph` in the background, so a
+        // degraded or unreachable RGS server degrades gracefully into a (slower, peer-limited)
+        // P2P view of the network rather than a frozen one.
         let route_handler = match &gossip_source {
-            GossipSource::P2pNetwork { gossip_sync } => {
-                gossip_sync.clone() as Arc<dyn RoutingMessageHandler + Sync + Send>
-            }
-            GossipSource::RapidGossipSync { .. } => {
-                Arc::new(IgnoringMessageHandler {}) as Arc<dyn RoutingMessageHandler + Sync + Send>
-            }
-        };
+            GossipSource::P2pNetwork { gossip_sync } => gossip_sync.clone(),
+            GossipSource::RapidGossipSync { .. } => Arc::new(P2pGossipSync::new(
+                network_graph.clone(),
+                None::<Arc<dyn UtxoLookup + Send + Sync>>,
+                logger.clone(),
+            )),
+        } as Arc<dyn RoutingMessageHandler + Sync + Send>;
 
         let onion_message_handler = Arc::new(TenTenOneOnionMessageHandler::new(
             node_event_handler.clone(),
@@ -509,9 +594,35 @@ impl<S: TenTenOneStorage + 'static, N: Storage + Sync + Send + 'static> Node<S,
             oracle_pubkey,
             probes: Probes::default(),
             event_handler: node_event_handler,
+            bump_tx_event_handler,
+            peer_store,
+            payment_store,
         })
     }
 
+    /// Services a [`BumpTransactionEvent`], fee-bumping the given anchor-channel commitment or
+    /// HTLC transaction via CPFP. Should be called by the node's [`EventHandlerTrait`]
+    /// implementation whenever `Event::BumpTransaction` fires.
+    pub fn handle_bump_transaction_event(&self, event: &BumpTransactionEvent) {
+        self.bump_tx_event_handler.handle_event(event);
+    }
+
+    /// Records or updates a payment in the durable [`PaymentStore`]. Should be called by the
+    /// node's [`EventHandlerTrait`] implementation whenever `PaymentClaimable`, `PaymentSent` or
+    /// `PaymentFailed` fires.
+    pub fn record_payment(
+        &self,
+        payment_hash: lightning::ln::PaymentHash,
+        payment: PaymentDetails,
+    ) -> Result<()> {
+        self.payment_store.upsert(payment_hash, payment)
+    }
+
+    /// Looks up a previously recorded payment by its hash.
+    pub fn get_payment(&self, payment_hash: &lightning::ln::PaymentHash) -> Option<PaymentDetails> {
+        self.payment_store.get(payment_hash)
+    }
+
     /// Starts the background handles - if the returned handles are dropped, the
     /// background tasks are stopped.
     // TODO: Consider having handles for *all* the tasks & threads for a clean shutdown.
@@ -523,8 +634,15 @@ impl<S: TenTenOneStorage + 'static, N: Storage + Sync + Send + 'static> Node<S,
         let mut handles = vec![spawn_connection_management(
             self.peer_manager.clone(),
             self.listen_address,
+            self.peer_store.clone(),
         )];
 
+        tokio::spawn(reconnect_to_peers(
+            self.peer_manager.clone(),
+            self.channel_manager.clone(),
+            self.peer_store.clone(),
+        ));
+
         std::thread::spawn(shadow_sync_periodically(
             self.settings.clone(),
             self.node_storage.clone(),
@@ -532,6 +650,11 @@ impl<S: TenTenOneStorage + 'static, N: Storage + Sync + Send + 'static> Node<S,
             self.channel_manager.clone(),
         ));
 
+        tokio::spawn(periodic_on_chain_wallet_sync(
+            self.wallet.clone(),
+            self.settings.clone(),
+        ));
+
         tokio::spawn(periodic_lightning_wallet_sync(
             self.channel_manager.clone(),
             self.chain_monitor.clone(),
@@ -544,6 +667,12 @@ impl<S: TenTenOneStorage + 'static, N: Storage + Sync + Send + 'static> Node<S,
             self.fee_rate_estimator.clone(),
         ));
 
+        tokio::spawn(periodic_scorer_persist(
+            self.scorer.clone(),
+            self.ln_storage.clone(),
+            self.settings.clone(),
+        ));
+
         handles.push(spawn_background_processor(
             self.peer_manager.clone(),
             self.channel_manager.clone(),
@@ -580,11 +709,12 @@ impl<S: TenTenOneStorage + 'static, N: Storage + Sync + Send + 'static> Node<S,
         ));
 
         tokio::spawn(manage_spendable_outputs_task(
-            self.esplora_server_url.clone(),
+            self.esplora_client.clone(),
             self.node_storage.clone(),
             self.wallet.clone(),
             self.fee_rate_estimator.clone(),
             self.keys_manager.clone(),
+            self.settings.clone(),
         ));
 
         tracing::info!("Lightning node started with node ID {}", self.info);
@@ -624,32 +754,8 @@ impl<S: TenTenOneStorage + 'static, N: Storage + Sync + Send + 'static> Node<S,
         .await
     }
 
-    /// Returns a closure which triggers an on-chain sync and subsequently updates the address
-    /// cache, at an interval.
-    ///
-    /// The task will loop at an interval determined by the node's [`LnDlcNodeSettings`].
-    ///
-    /// Suitable for daemons such as the coordinator and the maker.
-    pub fn sync_on_chain_wallet_periodically(&self) -> impl Fn() {
-        let handle = tokio::runtime::Handle::current();
-        let settings = self.settings.clone();
-        let ln_dlc_wallet = self.wallet.clone();
-        move || loop {
-            if let Err(e) = ln_dlc_wallet.sync_and_update_address_cache() {
-                tracing::error!("Failed on-chain sync: {e:#}");
-            }
-
-            let interval = handle.block_on(async {
-                let guard = settings.read().await;
-                guard.on_chain_sync_interval
-            });
-
-            std::thread::sleep(interval);
-        }
-    }
-
-    pub fn sync_on_chain_wallet(&self) -> Result<()> {
-        self.wallet.sync_and_update_address_cache()
+    pub async fn sync_on_chain_wallet(&self) -> Result<()> {
+        self.wallet.sync_and_update_address_cache_async().await
     }
 
     pub fn sync_lightning_wallet(&self) -> Result<()> {
@@ -703,6 +809,39 @@ async fn update_fee_rate_estimates(
     }
 }
 
+/// Periodically persists the [`Scorer`], independently of
+/// [`lightning_background_processor::process_events_async`]'s own persistence timer.
+///
+/// The scorer's liquidity estimates decay lazily with the passage of time as they are read, so
+/// there is nothing to explicitly "tick" here; simply persisting on a regular cadence is enough
+/// to make sure we don't lose a session's worth of learned routing data if the node goes down
+/// between background-processor persists.
+async fn periodic_scorer_persist<S: TenTenOneStorage>(
+    scorer: Arc<std::sync::RwLock<Scorer>>,
+    storage: Arc<S>,
+    settings: Arc<RwLock<LnDlcNodeSettings>>,
+) {
+    loop {
+        let interval = { settings.read().await.scorer_persist_interval };
+        tokio::time::sleep(interval).await;
+
+        let bytes = scorer
+            .read()
+            .expect("scorer lock to not be poisoned")
+            .encode();
+
+        if let Err(e) = KVStore::write(
+            storage.as_ref(),
+            SCORER_PERSISTENCE_PRIMARY_NAMESPACE,
+            SCORER_PERSISTENCE_SECONDARY_NAMESPACE,
+            SCORER_PERSISTENCE_KEY,
+            bytes,
+        ) {
+            tracing::error!("Failed to persist scorer: {e:#}");
+        }
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 fn spawn_background_processor<S: TenTenOneStorage + 'static, N: Storage + Sync + Send + 'static>(
     peer_manager: Arc<PeerManager<S, N>>,
@@ -744,15 +883,52 @@ fn spawn_background_processor<S: TenTenOneStorage + 'static, N: Storage + Sync +
     remote_handle
 }
 
-async fn periodic_lightning_wallet_sync<S: TenTenOneStorage, N: Storage + Sync + Send>(
+/// Periodically triggers an on-chain sync and updates the address cache.
+///
+/// Unlike the old thread-and-blocking-call based implementation, this drives the sync through the
+/// async Esplora interface so it `.await`s network IO instead of parking a runtime thread.
+async fn periodic_on_chain_wallet_sync<S: TenTenOneStorage, N: Storage + Sync + Send>(
+    ln_dlc_wallet: Arc<LnDlcWallet<S, N>>,
+    settings: Arc<RwLock<LnDlcNodeSettings>>,
+) {
+    loop {
+        if let Err(e) = ln_dlc_wallet.sync_and_update_address_cache_async().await {
+            tracing::error!("Failed on-chain sync: {e:#}");
+        }
+
+        let interval = {
+            let guard = settings.read().await;
+            guard.on_chain_sync_interval
+        };
+
+        tokio::time::sleep(interval).await;
+    }
+}
+
+async fn periodic_lightning_wallet_sync<
+    S: TenTenOneStorage + 'static,
+    N: Storage + Sync + Send + 'static,
+>(
     channel_manager: Arc<ChannelManager<S, N>>,
     chain_monitor: Arc<ChainMonitor<S, N>>,
     settings: Arc<RwLock<LnDlcNodeSettings>>,
     esplora_client: Arc<EsploraSyncClient<Arc<TracingLogger>>>,
 ) {
     loop {
-        if let Err(e) = lightning_wallet_sync(&channel_manager, &chain_monitor, &esplora_client) {
-            tracing::error!("Background sync of Lightning wallet failed: {e:#}")
+        // `lightning_wallet_sync` blocks the calling thread on network IO (see its doc comment),
+        // so it has to run on a blocking-pool thread rather than directly on this async task.
+        let result = spawn_blocking({
+            let channel_manager = channel_manager.clone();
+            let chain_monitor = chain_monitor.clone();
+            let esplora_client = esplora_client.clone();
+            move || lightning_wallet_sync(&channel_manager, &chain_monitor, &esplora_client)
+        })
+        .await;
+
+        match result {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => tracing::error!("Background sync of Lightning wallet failed: {e:#}"),
+            Err(e) => tracing::error!("Lightning wallet sync task panicked: {e:#}"),
         }
 
         let interval = {
@@ -763,6 +939,14 @@ async fn periodic_lightning_wallet_sync<S: TenTenOneStorage, N: Storage + Sync +
     }
 }
 
+/// Drives the [`Confirm`] sync for `channel_manager` and `chain_monitor` against the Esplora
+/// client.
+///
+/// This blocks the calling thread on network IO. `esplora_client` is built against
+/// `lightning-transaction-sync`'s `esplora-blocking` feature, so there is no async `.sync()` to
+/// `.await` here; switching to the `esplora-async` feature (to stop monopolising a runtime thread)
+/// needs a manifest change that has to land in the same commit as the call-site flip, which this
+/// tree can't make since no `Cargo.toml` exists for this crate.
 fn lightning_wallet_sync<S: TenTenOneStorage, N: Storage + Sync + Send>(
     channel_manager: &ChannelManager<S, N>,
     chain_monitor: &ChainMonitor<S, N>,
@@ -817,6 +1001,7 @@ fn spawn_connection_management<
 >(
     peer_manager: Arc<PeerManager<S, N>>,
     listen_address: SocketAddr,
+    peer_store: Arc<PeerStore<S>>,
 ) -> RemoteHandle<()> {
     let (fut, remote_handle) = async move {
         let mut connection_handles = Vec::new();
@@ -836,12 +1021,15 @@ fn spawn_connection_management<
 
             tracing::debug!(%addr, "Received inbound connection");
 
+            let peer_store = peer_store.clone();
             let (fut, connection_handle) = async move {
                 lightning_net_tokio::setup_inbound(
                     peer_manager.clone(),
                     tcp_stream.into_std().expect("Stream conversion to succeed"),
                 )
                 .await;
+
+                remember_peer_at_address(&peer_manager, &peer_store, addr);
             }
             .remote_handle();
 
@@ -859,6 +1047,147 @@ fn spawn_connection_management<
     remote_handle
 }
 
+/// Looks up which peer, if any, just connected from `addr` and persists it to the
+/// [`PeerStore`] so that we can re-dial it after a restart.
+fn remember_peer_at_address<S: TenTenOneStorage + 'static, N: Storage + Send + Sync + 'static>(
+    peer_manager: &PeerManager<S, N>,
+    peer_store: &PeerStore<S>,
+    addr: SocketAddr,
+) {
+    let ip_octets = match addr.ip() {
+        std::net::IpAddr::V4(ip) => ip.octets(),
+        // LDK's `SocketAddress::TcpIpV4` only represents IPv4 peers, so there's nothing to
+        // match an IPv6 address against.
+        std::net::IpAddr::V6(_) => return,
+    };
+
+    let expected_addr = SocketAddress::TcpIpV4 {
+        addr: ip_octets,
+        port: addr.port(),
+    };
+
+    let peer_id = peer_manager
+        .get_peer_node_ids()
+        .into_iter()
+        .find(|(_, peer_addr)| peer_addr.as_ref() == Some(&expected_addr))
+        .map(|(pubkey, _)| pubkey);
+
+    if let Some(peer_id) = peer_id {
+        if let Ok(address) = SocketAddress::from_str(&addr.to_string()) {
+            if let Err(e) = peer_store.update_peer(peer_id, address) {
+                tracing::warn!(%peer_id, "Failed to persist inbound peer. Error: {e:#}");
+            }
+        }
+    }
+}
+
+/// The base and cap of the exponential backoff applied to a peer that keeps failing to
+/// reconnect, so that an unreachable peer doesn't get redialled every single tick.
+const RECONNECT_BACKOFF_BASE: Duration = Duration::from_secs(5);
+const RECONNECT_BACKOFF_MAX: Duration = Duration::from_secs(10 * 60);
+
+/// Periodically re-dials, with exponential backoff, any channel peer or previously-connected
+/// peer from the [`PeerStore`] that we are not currently connected to.
+///
+/// Runs its first pass immediately, so that on startup we eagerly reconnect before waiting out a
+/// full [`RECONNECT_TO_PEERS_INTERVAL`]. Mirrors the `peer_store.rs` reconnect pattern used by
+/// `ldk-node`.
+async fn reconnect_to_peers<S: TenTenOneStorage + 'static, N: Storage + Send + Sync + 'static>(
+    peer_manager: Arc<PeerManager<S, N>>,
+    channel_manager: Arc<ChannelManager<S, N>>,
+    peer_store: Arc<PeerStore<S>>,
+) {
+    let mut backoff: HashMap<PublicKey, (Duration, Instant)> = HashMap::new();
+
+    loop {
+        let connected_peers = peer_manager
+            .get_peer_node_ids()
+            .into_iter()
+            .map(|(pubkey, _)| pubkey)
+            .collect::<std::collections::HashSet<_>>();
+
+        let known_peers = peer_store.peers();
+
+        // Channel peers take priority: a channel we can't reach is a channel that can't route
+        // payments. Peers with no persisted address are skipped, since we have nowhere to dial.
+        let channel_peers = channel_manager
+            .list_channels()
+            .into_iter()
+            .map(|channel| channel.counterparty.node_id)
+            .collect::<std::collections::HashSet<_>>();
+
+        // Dedupe before dialing: a channel counterparty that also has a persisted address in
+        // `peer_store` (the common case) would otherwise be visited twice in the same tick,
+        // attempting a redundant `connect_outbound` against it.
+        let reconnect_order = channel_peers.iter().copied().chain(
+            known_peers
+                .keys()
+                .copied()
+                .filter(|pubkey| !channel_peers.contains(pubkey)),
+        );
+
+        for pubkey in reconnect_order {
+            if connected_peers.contains(&pubkey) {
+                backoff.remove(&pubkey);
+                continue;
+            }
+
+            if let Some((_, next_attempt_at)) = backoff.get(&pubkey) {
+                if Instant::now() < *next_attempt_at {
+                    continue;
+                }
+            }
+
+            let address = match known_peers.get(&pubkey) {
+                Some(address) => address.clone(),
+                None => continue,
+            };
+
+            let socket_addr = match address.to_socket_addrs() {
+                Ok(mut addrs) => match addrs.next() {
+                    Some(addr) => addr,
+                    None => continue,
+                },
+                Err(_) => continue,
+            };
+
+            tracing::debug!(%pubkey, %socket_addr, "Reconnecting to known peer");
+
+            let succeeded = match lightning_net_tokio::connect_outbound(
+                peer_manager.clone(),
+                pubkey,
+                socket_addr,
+            )
+            .await
+            {
+                Some(connection_closed) => {
+                    tokio::spawn(async move {
+                        connection_closed.await;
+                        tracing::debug!(%pubkey, "Connection to known peer closed");
+                    });
+                    true
+                }
+                None => {
+                    tracing::debug!(%pubkey, %socket_addr, "Failed to reconnect to known peer");
+                    false
+                }
+            };
+
+            if succeeded {
+                backoff.remove(&pubkey);
+            } else {
+                let next_backoff = backoff
+                    .get(&pubkey)
+                    .map(|(previous, _)| (*previous * 2).min(RECONNECT_BACKOFF_MAX))
+                    .unwrap_or(RECONNECT_BACKOFF_BASE);
+                backoff.insert(pubkey, (next_backoff, Instant::now() + next_backoff));
+            }
+        }
+
+        tokio::time::sleep(RECONNECT_TO_PEERS_INTERVAL).await;
+    }
+}
+
 fn spawn_broadcast_node_annoucements<
     S: TenTenOneStorage + 'static,
     N: Storage + Sync + Send + 'static,
@@ -891,39 +1220,61 @@ async fn manage_spendable_outputs_task<
     S: TenTenOneStorage + 'static,
     N: Storage + Sync + Send + 'static,
 >(
-    esplora_server_url: String,
+    esplora_client: Arc<NodeEsploraClient>,
     node_storage: Arc<N>,
     ln_dlc_wallet: Arc<LnDlcWallet<S, N>>,
     fee_rate_estimator: Arc<FeeRateEstimator>,
     keys_manager: Arc<CustomKeysManager<S, N>>,
+    settings: Arc<RwLock<LnDlcNodeSettings>>,
 ) {
-    let client = Arc::new(esplora_client::BlockingClient::from_agent(
-        esplora_server_url,
-        ureq::agent(),
-    ));
     loop {
-        if let Err(e) = spawn_blocking({
-            let client = client.clone();
-            let node_storage = node_storage.clone();
-            let ln_dlc_wallet = ln_dlc_wallet.clone();
-            let fee_rate_estimator = fee_rate_estimator.clone();
-            let keys_manager = keys_manager.clone();
-            move || {
-                manage_spendable_outputs(
-                    node_storage,
-                    client,
-                    ln_dlc_wallet,
-                    fee_rate_estimator,
-                    keys_manager,
-                )
-            }
-        })
-        .await
-        .expect("task to complete")
-        {
-            tracing::error!("Failed to deal with spendable outputs: {e:#}");
+        let sweep_settings = settings.read().await.spendable_output_sweep.clone();
+
+        let result = match &sweep_settings {
+            Some(sweep_settings) => spawn_blocking({
+                let node_storage = node_storage.clone();
+                let ln_dlc_wallet = ln_dlc_wallet.clone();
+                let fee_rate_estimator = fee_rate_estimator.clone();
+                let keys_manager = keys_manager.clone();
+                let sweep_settings = sweep_settings.clone();
+                move || {
+                    let descriptors = node_storage.spendable_outputs()?;
+
+                    if sweep_spendable_outputs_to_cold_storage(
+                        &descriptors,
+                        &sweep_settings,
+                        &ln_dlc_wallet,
+                        &fee_rate_estimator,
+                        &keys_manager,
+                    )?
+                    .is_none()
+                    {
+                        // Not enough value has accumulated yet; leave the descriptors in place
+                        // for the next tick rather than recycling them into the hot wallet.
+                        return Ok(());
+                    }
+
+                    node_storage.clear_spendable_outputs(&descriptors)
+                }
+            })
+            .await
+            .expect("task to complete"),
+            // Reuses the Esplora client of the Lightning wallet sync, instead of spinning up an
+            // independent connection just for this fallback.
+            None => manage_spendable_outputs(
+                node_storage.clone(),
+                esplora_client.client().clone(),
+                ln_dlc_wallet.clone(),
+                fee_rate_estimator.clone(),
+                keys_manager.clone(),
+            )
+            .await,
         };
 
+        if let Err(e) = result {
+            tracing::error!("Failed to deal with spendable outputs: {e:#}");
+        }
+
         tokio::time::sleep(MANAGE_SPENDABLE_OUTPUTS_INTERVAL).await;
     }
 }
@@ -1019,17 +1370,46 @@ fn spawn_keep_rgs_snapshot_up_to_date(gossip_source: Arc<GossipSource>) -> Remot
                 .get_last_rapid_gossip_sync_timestamp()
                 .unwrap_or_default();
 
+            let mut consecutive_failures = 0;
+
             loop {
-                match update_rgs_snapshot(gossip_sync.clone(), server_url, latest_sync_timestamp)
-                    .await
+                let sleep_duration = match update_rgs_snapshot(
+                    gossip_sync.clone(),
+                    server_url,
+                    latest_sync_timestamp,
+                )
+                .await
                 {
-                    Ok(timestamp) => latest_sync_timestamp = timestamp,
+                    Ok(timestamp) => {
+                        if consecutive_failures >= RGS_DEGRADED_FAILURE_THRESHOLD {
+                            tracing::info!(
+                                "RGS snapshot source recovered"
+                            );
+                        }
+
+                        consecutive_failures = 0;
+                        latest_sync_timestamp = timestamp;
+
+                        RGS_SYNC_INTERVAL
+                    }
                     Err(e) => {
-                        tracing::error!("Failed to update RGS snapshot: {e:#}");
+                        consecutive_failures += 1;
+                        tracing::error!(consecutive_failures, "Failed to update RGS snapshot: {e:#}");
+
+                        if consecutive_failures == RGS_DEGRADED_FAILURE_THRESHOLD {
+                            tracing::warn!(
+                                "RGS snapshot source looks degraded; relying on the passive P2P \
+                                 gossip sync to keep the network graph fresh in the meantime"
+                            );
+                        }
+
+                        RGS_FAILURE_BACKOFF_BASE
+                            .saturating_mul(1 << (consecutive_failures - 1).min(16))
+                            .min(RGS_FAILURE_BACKOFF_MAX)
                     }
-                }
+                };
 
-                tokio::time::sleep(RGS_SYNC_INTERVAL).await;
+                tokio::time::sleep(sleep_duration).await;
             }
         }
     }
@@ -1059,10 +1439,29 @@ async fn update_rgs_snapshot(
         .await
         .context("Failed to get RGS gossip update response bytes")?;
 
+    // An empty response means the server has nothing newer than `latest_sync_timestamp` for us;
+    // that is not a failure, we just have nothing to apply this round.
+    if update_data.is_empty() {
+        tracing::debug!(%latest_sync_timestamp, "RGS snapshot already up to date, no new data");
+        return Ok(latest_sync_timestamp);
+    }
+
     let new_latest_sync_timestamp = gossip_sync
         .update_network_graph(&update_data)
         .map_err(|e| anyhow!("Failed to update network graph: {e:?}"))?;
 
+    // The snapshot timestamp returned by RGS is meant to be used as the `last_sync_timestamp` in
+    // the next query, so it must never move backwards; a server bug or a replayed response could
+    // otherwise wedge us into re-requesting (and re-applying) old snapshots forever.
+    if new_latest_sync_timestamp < latest_sync_timestamp {
+        tracing::warn!(
+            %new_latest_sync_timestamp,
+            %latest_sync_timestamp,
+            "Ignoring non-monotonic RGS snapshot timestamp"
+        );
+        return Ok(latest_sync_timestamp);
+    }
+
     tracing::info!(%new_latest_sync_timestamp, "Updated network graph");
 
     Ok(new_latest_sync_timestamp)
@@ -0,0 +1,176 @@
+use crate::node::invoice::HTLCStatus;
+use crate::node::PaymentDetails;
+use crate::node::PaymentDirection;
+use crate::storage::TenTenOneStorage;
+use crate::ChannelManager;
+use crate::Storage;
+use anyhow::Context;
+use anyhow::Result;
+use bitcoin::hashes::hex::FromHex;
+use bitcoin::hashes::hex::ToHex;
+use lightning::ln::channelmanager::RecentPaymentDetails;
+use lightning::ln::PaymentHash;
+use lightning::util::persist::KVStore;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::RwLock;
+
+pub(crate) const PAYMENT_STORE_PERSISTENCE_PRIMARY_NAMESPACE: &str = "payment_store";
+pub(crate) const PAYMENT_STORE_PERSISTENCE_SECONDARY_NAMESPACE: &str = "";
+pub(crate) const PAYMENT_STORE_PERSISTENCE_KEY: &str = "payments";
+
+/// Durable record of every inbound and outbound Lightning payment, keyed by [`PaymentHash`].
+///
+/// Persisted through the node's [`TenTenOneStorage`] so that after a crash the node can still
+/// answer "did this invoice get paid?" without waiting for LDK to replay its own payment history.
+pub struct PaymentStore<S> {
+    storage: Arc<S>,
+    payments: RwLock<HashMap<PaymentHash, PaymentDetails>>,
+}
+
+impl<S: TenTenOneStorage> PaymentStore<S> {
+    pub fn new(storage: Arc<S>) -> Self {
+        let payments = Self::load(&storage).unwrap_or_else(|e| {
+            tracing::warn!("Failed to load payment store, starting empty. Error: {e:#}");
+            HashMap::new()
+        });
+
+        Self {
+            storage,
+            payments: RwLock::new(payments),
+        }
+    }
+
+    pub fn get(&self, payment_hash: &PaymentHash) -> Option<PaymentDetails> {
+        self.payments
+            .read()
+            .expect("payment store lock to not be poisoned")
+            .get(payment_hash)
+            .cloned()
+    }
+
+    pub fn upsert(&self, payment_hash: PaymentHash, payment: PaymentDetails) -> Result<()> {
+        {
+            let mut payments = self
+                .payments
+                .write()
+                .expect("payment store lock to not be poisoned");
+            payments.insert(payment_hash, payment);
+        }
+
+        self.persist()
+    }
+
+    /// Reconciles the payment store against LDK's own view of in-flight payments.
+    ///
+    /// `channel_manager.list_recent_payments()` only reports **outbound** payment attempts, so
+    /// this can only resolve stuck *outbound* payments: any we still consider pending, but that
+    /// `channel_manager` no longer knows about (i.e. it abandoned it while we were down), is
+    /// resolved to a terminal [`HTLCStatus::Failed`] rather than being left stuck. Inbound
+    /// payments aren't touched here — LDK has no equivalent "recent inbound payments" API, so a
+    /// still-pending inbound invoice is left alone rather than being marked failed on every
+    /// restart.
+    pub fn reconcile_with_channel_manager<N: Storage + Sync + Send + 'static>(
+        &self,
+        channel_manager: &ChannelManager<S, N>,
+    ) -> Result<()> {
+        let still_known = channel_manager
+            .list_recent_payments()
+            .into_iter()
+            .filter_map(|details| match details {
+                RecentPaymentDetails::Pending { payment_hash, .. } => Some(payment_hash),
+                // LDK still knows about a fulfilled payment, so it must not be marked failed.
+                RecentPaymentDetails::Fulfilled { payment_hash, .. } => payment_hash.into(),
+                RecentPaymentDetails::Abandoned { .. } => None,
+            })
+            .collect::<std::collections::HashSet<_>>();
+
+        let stale_pending_hashes = {
+            let payments = self
+                .payments
+                .read()
+                .expect("payment store lock to not be poisoned");
+
+            payments
+                .iter()
+                .filter(|(hash, details)| {
+                    details.direction == PaymentDirection::Outbound
+                        && details.status == HTLCStatus::Pending
+                        && !still_known.contains(hash)
+                })
+                .map(|(hash, _)| *hash)
+                .collect::<Vec<_>>()
+        };
+
+        if stale_pending_hashes.is_empty() {
+            return Ok(());
+        }
+
+        tracing::warn!(
+            count = stale_pending_hashes.len(),
+            "Found outbound payments stuck pending after restart; marking them as failed"
+        );
+
+        {
+            let mut payments = self
+                .payments
+                .write()
+                .expect("payment store lock to not be poisoned");
+            for hash in &stale_pending_hashes {
+                if let Some(payment) = payments.get_mut(hash) {
+                    payment.status = HTLCStatus::Failed;
+                }
+            }
+        }
+
+        self.persist()
+    }
+
+    fn load(storage: &Arc<S>) -> Result<HashMap<PaymentHash, PaymentDetails>> {
+        let bytes = match KVStore::read(
+            storage.as_ref(),
+            PAYMENT_STORE_PERSISTENCE_PRIMARY_NAMESPACE,
+            PAYMENT_STORE_PERSISTENCE_SECONDARY_NAMESPACE,
+            PAYMENT_STORE_PERSISTENCE_KEY,
+        ) {
+            Ok(bytes) => bytes,
+            Err(_) => return Ok(HashMap::new()),
+        };
+
+        let payments: HashMap<String, PaymentDetails> =
+            serde_json::from_slice(&bytes).context("Failed to deserialize payment store")?;
+
+        let payments = payments
+            .into_iter()
+            .filter_map(|(hash, details)| {
+                let bytes = <[u8; 32]>::from_hex(&hash).ok()?;
+                Some((PaymentHash(bytes), details))
+            })
+            .collect();
+
+        Ok(payments)
+    }
+
+    fn persist(&self) -> Result<()> {
+        let payments = self
+            .payments
+            .read()
+            .expect("payment store lock to not be poisoned");
+
+        let payments = payments
+            .iter()
+            .map(|(hash, details)| (hash.0.to_hex(), details.clone()))
+            .collect::<HashMap<String, PaymentDetails>>();
+
+        let bytes = serde_json::to_vec(&payments).context("Failed to serialize payment store")?;
+
+        KVStore::write(
+            self.storage.as_ref(),
+            PAYMENT_STORE_PERSISTENCE_PRIMARY_NAMESPACE,
+            PAYMENT_STORE_PERSISTENCE_SECONDARY_NAMESPACE,
+            PAYMENT_STORE_PERSISTENCE_KEY,
+            bytes,
+        )
+        .context("Failed to persist payment store")
+    }
+}
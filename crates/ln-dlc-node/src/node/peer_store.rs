@@ -0,0 +1,112 @@
+use crate::storage::TenTenOneStorage;
+use anyhow::Context;
+use anyhow::Result;
+use bitcoin::secp256k1::PublicKey;
+use lightning::ln::msgs::SocketAddress;
+use lightning::util::persist::KVStore;
+use lightning::util::ser::Readable;
+use lightning::util::ser::Writeable;
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::sync::Arc;
+use std::sync::RwLock;
+
+pub(crate) const PEER_STORE_PERSISTENCE_PRIMARY_NAMESPACE: &str = "peer_store";
+pub(crate) const PEER_STORE_PERSISTENCE_SECONDARY_NAMESPACE: &str = "";
+pub(crate) const PEER_STORE_PERSISTENCE_KEY: &str = "peers";
+
+/// Durable record of peers and the address they were seen at, so that we can automatically
+/// re-dial them after a restart or a dropped TCP connection.
+///
+/// Entries are added through [`PeerStore::update_peer`], which the node calls for inbound
+/// connections only; outbound connections are not recorded here.
+///
+/// Persisted through the node's [`TenTenOneStorage`] under [`PEER_STORE_PERSISTENCE_KEY`].
+pub struct PeerStore<S> {
+    storage: Arc<S>,
+    peers: RwLock<HashMap<PublicKey, SocketAddress>>,
+}
+
+impl<S: TenTenOneStorage> PeerStore<S> {
+    pub fn new(storage: Arc<S>) -> Self {
+        let peers = Self::load(&storage).unwrap_or_else(|e| {
+            tracing::warn!("Failed to load peer store, starting empty. Error: {e:#}");
+            HashMap::new()
+        });
+
+        Self {
+            storage,
+            peers: RwLock::new(peers),
+        }
+    }
+
+    /// All peers we have ever connected to, keyed by node ID.
+    pub fn peers(&self) -> HashMap<PublicKey, SocketAddress> {
+        self.peers
+            .read()
+            .expect("peer store lock to not be poisoned")
+            .clone()
+    }
+
+    /// Remembers that we have successfully connected to `peer` at `address`, persisting the
+    /// updated peer set immediately.
+    pub fn update_peer(&self, peer: PublicKey, address: SocketAddress) -> Result<()> {
+        {
+            let mut peers = self.peers.write().expect("peer store lock to not be poisoned");
+            match peers.get(&peer) {
+                Some(existing) if existing == &address => return Ok(()),
+                _ => {
+                    peers.insert(peer, address);
+                }
+            }
+        }
+
+        self.persist()
+    }
+
+    fn load(storage: &Arc<S>) -> Result<HashMap<PublicKey, SocketAddress>> {
+        let bytes = match KVStore::read(
+            storage.as_ref(),
+            PEER_STORE_PERSISTENCE_PRIMARY_NAMESPACE,
+            PEER_STORE_PERSISTENCE_SECONDARY_NAMESPACE,
+            PEER_STORE_PERSISTENCE_KEY,
+        ) {
+            Ok(bytes) => bytes,
+            Err(_) => return Ok(HashMap::new()),
+        };
+
+        let mut reader = Cursor::new(bytes);
+
+        let len: u64 = Readable::read(&mut reader).context("Failed to read peer store length")?;
+        let mut peers = HashMap::with_capacity(len as usize);
+        for _ in 0..len {
+            let pubkey: PublicKey =
+                Readable::read(&mut reader).context("Failed to read peer store pubkey")?;
+            let address: SocketAddress =
+                Readable::read(&mut reader).context("Failed to read peer store address")?;
+            peers.insert(pubkey, address);
+        }
+
+        Ok(peers)
+    }
+
+    fn persist(&self) -> Result<()> {
+        let peers = self.peers.read().expect("peer store lock to not be poisoned");
+
+        let mut buf = Vec::new();
+        (peers.len() as u64).write(&mut buf)?;
+        for (pubkey, address) in peers.iter() {
+            pubkey.write(&mut buf)?;
+            address.write(&mut buf)?;
+        }
+
+        KVStore::write(
+            self.storage.as_ref(),
+            PEER_STORE_PERSISTENCE_PRIMARY_NAMESPACE,
+            PEER_STORE_PERSISTENCE_SECONDARY_NAMESPACE,
+            PEER_STORE_PERSISTENCE_KEY,
+            buf,
+        )
+        .context("Failed to persist peer store")
+    }
+}
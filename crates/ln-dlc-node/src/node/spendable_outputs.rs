@@ -0,0 +1,93 @@
+use crate::dlc_custom_signer::CustomKeysManager;
+use crate::fee_rate_estimator::FeeRateEstimator;
+use crate::ln_dlc_wallet::LnDlcWallet;
+use crate::storage::TenTenOneStorage;
+use crate::Storage;
+use anyhow::Context;
+use anyhow::Result;
+use bitcoin::Address;
+use bitcoin::Amount;
+use bitcoin::FeeRate;
+use lightning::chain::chaininterface::ConfirmationTarget;
+use lightning::sign::SpendableOutputDescriptor;
+use serde::Deserialize;
+use serde::Serialize;
+use std::sync::Arc;
+
+/// An optional cold-storage sweep policy for LDK-generated [`SpendableOutputDescriptor`]s (e.g.
+/// channel-close and HTLC-claim outputs).
+///
+/// When configured, [`sweep_spendable_outputs_to_cold_storage`] pays matured spendable outputs
+/// straight to `destination` instead of recycling them into the node's hot on-chain wallet.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct SpendableOutputSweepSettings {
+    /// The address that spendable outputs get swept to.
+    pub destination: Address,
+    /// Spendable outputs are only swept once their aggregate value exceeds this threshold.
+    pub min_sweep_value_sats: u64,
+    /// The confirmation target used to estimate the feerate for the sweep transaction.
+    pub confirmation_target: ConfirmationTarget,
+}
+
+/// Batches all currently-spendable `descriptors` into a single transaction paying
+/// `sweep_settings.destination`, provided their aggregate value exceeds the configured threshold.
+///
+/// Returns `Ok(None)` if the descriptors did not exceed the threshold; nothing is swept or
+/// broadcast then, and the descriptors stay unspent.
+pub fn sweep_spendable_outputs_to_cold_storage<S: TenTenOneStorage, N: Storage>(
+    descriptors: &[SpendableOutputDescriptor],
+    sweep_settings: &SpendableOutputSweepSettings,
+    ln_dlc_wallet: &LnDlcWallet<S, N>,
+    fee_rate_estimator: &FeeRateEstimator,
+    keys_manager: &CustomKeysManager<S, N>,
+) -> Result<Option<bitcoin::Txid>> {
+    let aggregate_value_sats: u64 = descriptors.iter().map(descriptor_value_sats).sum();
+
+    if aggregate_value_sats <= sweep_settings.min_sweep_value_sats {
+        tracing::debug!(
+            aggregate_value_sats,
+            min_sweep_value_sats = sweep_settings.min_sweep_value_sats,
+            "Not enough value in spendable outputs yet, deferring cold-storage sweep"
+        );
+        return Ok(None);
+    }
+
+    let fee_rate: FeeRate = fee_rate_estimator.get(sweep_settings.confirmation_target);
+
+    let change_destination_script = sweep_settings.destination.script_pubkey();
+
+    let tx = keys_manager
+        .inner()
+        .spend_spendable_outputs(
+            descriptors,
+            Vec::new(),
+            change_destination_script,
+            fee_rate,
+            None,
+            &bitcoin::secp256k1::Secp256k1::new(),
+        )
+        .context("Failed to build cold-storage sweep transaction")?;
+
+    let txid = tx.txid();
+
+    ln_dlc_wallet
+        .broadcast_transaction(&tx)
+        .context("Failed to broadcast cold-storage sweep transaction")?;
+
+    tracing::info!(
+        %txid,
+        aggregate_value_sats,
+        destination = %sweep_settings.destination,
+        "Swept spendable outputs to cold storage"
+    );
+
+    Ok(Some(txid))
+}
+
+fn descriptor_value_sats(descriptor: &SpendableOutputDescriptor) -> u64 {
+    match descriptor {
+        SpendableOutputDescriptor::StaticOutput { output, .. } => output.value,
+        SpendableOutputDescriptor::DelayedPaymentOutput(output) => output.output.value,
+        SpendableOutputDescriptor::StaticPaymentOutput(output) => output.output.value,
+    }
+}